@@ -2,10 +2,11 @@ use std::thread::sleep;
 use std::time::Duration;
 
 use rxrs::operators::interval;
+use rxrs::scheduler::NewThreadScheduler;
 use rxrs::subscription::Unsubscribable;
 
 fn main() {
-    let obs = interval(1);
+    let obs = interval(Duration::from_millis(1), NewThreadScheduler::new());
     let mut sub = obs.subscribe_next(|item| { println!("{}", item) });
 
     sleep(Duration::from_millis(5));
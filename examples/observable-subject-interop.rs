@@ -4,6 +4,7 @@ use std::time::Duration;
 use rxrs::observable::ObservableLike;
 use rxrs::operators::{interval, of};
 use rxrs::subject::Subject;
+use rxrs::scheduler::NewThreadScheduler;
 use rxrs::subscription::Unsubscribable;
 
 fn main() {
@@ -24,7 +25,7 @@ fn main() {
         || println!("complete"),
     );
 
-    let interval_observable = interval(1);
+    let interval_observable = interval(Duration::from_millis(1), NewThreadScheduler::new());
     let mut second_sub = interval_observable.subscribe(second_subject);
 
     sleep(Duration::from_millis(5));
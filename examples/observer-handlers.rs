@@ -1,8 +1,9 @@
 use rxrs::observable::{Observable, ObservableLike, Unsubscriber};
 use rxrs::observer::{Observer, ObserverLike};
+use rxrs::subscriber::Subscriber;
 
 fn main() {
-    let subscriber_fn = |mut observer: Observer<String>| {
+    let subscriber_fn = |mut observer: Subscriber<String>| {
         observer.next(&String::from("Oh"));
         observer.next(&String::from("hi"));
         observer.next(&String::from("Mark!"));
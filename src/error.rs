@@ -1,15 +1,46 @@
 use std::error::Error;
 use std::fmt;
+use std::sync::Arc;
 
-#[derive(Debug)]
+/// `RxError` is the default error type of an `Observable`.
+#[derive(Debug, Clone)]
 pub enum RxError {
-    CustomError(String)
+    CustomError(String),
+    /// A source didn't emit within its expected time.
+    Timeout,
+    /// A source completed without emitting any value, where at least one was expected.
+    EmptySequence,
+    /// An argument (e.g. an index or a count) is out of the range of the source.
+    ArgumentOutOfRange(String),
+    /// An event was sent through a subscription that is already unsubscribed.
+    Unsubscribed,
+    /// A handler of an observer panicked, with the given panic message.
+    HandlerPanicked(String),
+    /// Multiple errors happened, e.g. in several inner sources.
+    Composite(Vec<RxError>),
+    /// An external error, exposed as the `source` of the `RxError`. The error is reference
+    /// counted, so that it can be shared with every observer of a source.
+    Source(Arc<dyn Error + Send + Sync>),
+}
+
+impl RxError {
+    /// Wraps an external `error` into an `RxError`.
+    pub fn from_error<E>(error: E) -> RxError where E: Error + Send + Sync + 'static {
+        RxError::Source(Arc::new(error))
+    }
+}
+
+impl From<Box<dyn Error + Send + Sync>> for RxError {
+    fn from(error: Box<dyn Error + Send + Sync>) -> RxError {
+        RxError::Source(Arc::from(error))
+    }
 }
 
 impl Error for RxError {
-    fn description(&self) -> &str {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
-            RxError::CustomError(ref err) => err.as_str()
+            RxError::Source(ref err) => Some(err.as_ref()),
+            _ => None
         }
     }
 }
@@ -17,7 +48,17 @@ impl Error for RxError {
 impl fmt::Display for RxError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            RxError::CustomError(ref err) => fmt::Display::fmt(err, f)
+            RxError::CustomError(ref err) => fmt::Display::fmt(err, f),
+            RxError::Timeout => write!(f, "timeout"),
+            RxError::EmptySequence => write!(f, "sequence contains no elements"),
+            RxError::ArgumentOutOfRange(ref argument) => write!(f, "argument out of range: {}", argument),
+            RxError::Unsubscribed => write!(f, "already unsubscribed"),
+            RxError::HandlerPanicked(ref message) => write!(f, "handler panicked: {}", message),
+            RxError::Composite(ref errors) => {
+                write!(f, "{} errors occurred:", errors.len())?;
+                errors.iter().try_for_each(|err| write!(f, " [{}]", err))
+            }
+            RxError::Source(_) => write!(f, "source error")
         }
     }
-}
\ No newline at end of file
+}
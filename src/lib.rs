@@ -3,9 +3,12 @@
 pub mod error;
 pub mod observable;
 pub mod operators;
+pub mod scheduler;
 pub mod subject;
 pub mod observer;
+pub mod subscriber;
 pub mod subscription;
+pub mod testing;
 
 #[cfg(test)]
 mod tests;
\ No newline at end of file
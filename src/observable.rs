@@ -1,19 +1,29 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use crate::error::RxError;
 use crate::observer::{Observer, ObserverLike};
 use crate::operators::delay::DelayObserver;
-use crate::operators::filter::{FilterObserver, FilterPredicate};
-use crate::operators::map::{MapObserver, MapPredicate};
+use crate::operators::delay_when::DelayWhenObserver;
+use crate::operators::filter::FilterObserver;
+use crate::operators::map::MapObserver;
+use crate::operators::map_err::MapErrObserver;
+use crate::operators::reduce::ReduceObserver;
+use crate::operators::repeat::{RepeatNotifier, RepeatState};
+use crate::operators::scan::ScanObserver;
+use crate::scheduler::Scheduler;
+use crate::subscriber::{SafeObserver, Subscriber};
 use crate::subscription::{Subscription, Unsubscribable};
 
 /// `Unsubscriber` is a container for the function that should be called, once an `Observable`
 /// is unsubscribed. The `Unsubscriber` instance of an `Obseervable` is created once the
 /// observable has been subscribed.
 pub struct Unsubscriber {
-    func: Box<dyn FnMut()>
+    func: Box<dyn FnMut() + Send>
 }
 
 impl Unsubscriber {
-    pub fn new<F: 'static>(func: F) -> Unsubscriber where F: FnMut() {
+    pub fn new<F>(func: F) -> Unsubscriber where F: FnMut() + Send + 'static {
         Unsubscriber { func: Box::new(func) }
     }
 
@@ -23,119 +33,298 @@ impl Unsubscriber {
 }
 
 /// `ObservableConstructor` is a container for the logic of the Observable's creation.
-/// This function excepts as a parameter an `ObserverLike` variable and returns an `Unsubscriber`.
-struct ObservableConstructor<'a, O: ObserverLike> {
-    func: Box<dyn Fn(O) -> Unsubscriber + 'a>
+/// This function excepts as a parameter a `Subscriber` variable and returns an `Unsubscriber`.
+struct ObservableConstructor<'a, T, E> {
+    func: Box<dyn Fn(Subscriber<'a, T, E>) -> Unsubscriber + Send + Sync + 'a>
 }
 
-impl<'a, O: ObserverLike> ObservableConstructor<'a, O> {
-    pub fn new<F>(func: F) -> ObservableConstructor<'a, O>
-        where F: Fn(O) -> Unsubscriber + 'a {
+impl<'a, T, E> ObservableConstructor<'a, T, E> {
+    pub fn new<F>(func: F) -> ObservableConstructor<'a, T, E>
+        where F: Fn(Subscriber<'a, T, E>) -> Unsubscriber + Send + Sync + 'a {
         ObservableConstructor { func: Box::new(func) }
     }
 
-    pub fn call(&self, observer: O) -> Unsubscriber {
-        (self.func)(observer)
+    pub fn call(&self, subscriber: Subscriber<'a, T, E>) -> Unsubscriber {
+        (self.func)(subscriber)
     }
 }
 
 /// `Observable` is a representation of a collection of values over a period of time. Observables
 /// define event streams that can be subscribed to.
-pub struct Observable<'a, T, O> where O: ObserverLike<Value=T, Error=RxError> {
-    observer_fn: ObservableConstructor<'a, O>
+///
+/// The type of an `Observable` doesn't depend on its observers: any `ObserverLike` (an
+/// `Observer`, a `Subject` or a custom implementation) can subscribe to the same instance, and
+/// observables can be stored or returned from functions as `Observable<'a, T>`.
+///
+/// The error type `E` of an `Observable` is `RxError` by default, but can be any type (e.g. an
+/// application specific error enum). `map_err` converts between error types.
+pub struct Observable<'a, T, E = RxError> {
+    observer_fn: ObservableConstructor<'a, T, E>
 }
 
-impl<'a, T, O> Observable<'a, T, O> where O: ObserverLike<Value=T, Error=RxError> {
-    /// Creates a new `Observable` defined by a subscriber function.
-    pub fn new<F>(func: F) -> Observable<'a, T, O>
-        where F: Fn(O) -> Unsubscriber + 'a {
+impl<'a, T, E> Observable<'a, T, E> {
+    /// Creates a new `Observable` defined by a subscriber function. Synchronous sources should
+    /// check `is_closed` on the subscriber before every emission, so that they stop as soon as
+    /// the subscriber doesn't accept any further event.
+    ///
+    /// The subscriber function must be `Send + Sync`, so that operators (e.g. `repeat`) can
+    /// subscribe to an observable again from the thread where it completed.
+    pub fn new<F>(func: F) -> Observable<'a, T, E>
+        where F: Fn(Subscriber<'a, T, E>) -> Unsubscriber + Send + Sync + 'a {
         Observable { observer_fn: ObservableConstructor::new(func) }
     }
 }
 
-impl<'a, T: 'a, U: 'a, D> Observable<'a, T, MapObserver<T, U, D>>
-    where D: ObserverLike<Value=U, Error=RxError> + 'a {
+impl<'a, T: 'a, E: 'a> Observable<'a, T, E> {
     /// Maps the instance of an `Observable` into a new instance by mapping its internal value.
-    /// This mapping is defined by the `predicate` input parameter.
+    /// This mapping is defined by the `predicate` input parameter, which can be any closure
+    /// (capturing its environment if needed). Every subscription works on its own copy of the
+    /// closure.
     ///
     /// ```rust
     /// use rxrs::operators::of;
     /// use rxrs::observable::ObservableLike;
     ///
+    /// let prefix = String::from("item");
     /// let obs = of(&[1, 2, 3])
-    /// 	.map(|item| item.to_string());
+    ///     .map(move |item| format!("{} {}", prefix, item));
     ///
     /// obs.subscribe_next(|string| {
-    /// 	println!("{}", string);
+    ///     println!("{}", string);
     /// });
     /// ```
-    pub fn map(self, predicate: MapPredicate<T, U>) -> Observable<'a, U, D>
-        where D: ObserverLike<Value=U, Error=RxError> {
-        Observable::new(move |destination: D| {
-            let map_observer = MapObserver::new(destination, predicate);
+    pub fn map<U: 'a, F>(self, predicate: F) -> Observable<'a, U, E>
+        where F: FnMut(&T) -> U + Clone + Send + Sync + 'a {
+        Observable::new(move |destination: Subscriber<'a, U, E>| {
+            let map_observer = MapObserver::new(destination, predicate.clone());
             let mut subscription = self.subscribe(map_observer);
 
             Unsubscriber::new(move || subscription.unsubscribe())
         })
     }
-}
 
-impl<'a, T: 'a, O: 'a> Observable<'a, T, FilterObserver<T, O>>
-    where O: ObserverLike<Value=T, Error=RxError> {
     /// Filters the stream of values of an `Observable` and returns a new instance with the same
-    /// type definition. The filtering strategy is defined by the `predicate` input variable.
+    /// type definition. The filtering strategy is defined by the `predicate` closure.
     ///
     /// ```rust
     /// use rxrs::operators::of;
     ///
     /// let even = of(&[1, 2, 3])
-    /// 	.filter(|item| item % 2 == 0);
+    ///     .filter(|item| item % 2 == 0);
     ///
     /// even.subscribe_next(|number| println!("{}", number));
     ///
-    /// let odds = of(&[1, 2, 3])
-    /// 	.filter(|item| item % 2 == 1);
+    /// let threshold = 1;
+    /// let above = of(&[1, 2, 3])
+    ///     .filter(move |item| *item > threshold);
     ///
-    /// odds.subscribe_next(|number| println!("{}", number));
+    /// above.subscribe_next(|number| println!("{}", number));
     /// ```
-    pub fn filter(self, predicate: FilterPredicate<T>) -> Observable<'a, T, O> {
-        Observable::new(move |destination: O| {
-            let filter_observer = FilterObserver::new(destination, predicate);
+    pub fn filter<F>(self, predicate: F) -> Observable<'a, T, E>
+        where F: FnMut(&T) -> bool + Clone + Send + Sync + 'a {
+        Observable::new(move |destination: Subscriber<'a, T, E>| {
+            let filter_observer = FilterObserver::new(destination, predicate.clone());
             let mut subscription = self.subscribe(filter_observer);
 
             Unsubscriber::new(move || subscription.unsubscribe())
         })
     }
+
+    /// Maps the error of an `Observable` into a new error (of any type), as defined by the
+    /// `predicate` input parameter. The values and the complete event are not modified.
+    ///
+    /// ```rust
+    /// use rxrs::error::RxError;
+    /// use rxrs::operators::of;
+    ///
+    /// #[derive(Debug)]
+    /// enum ServiceError {
+    ///     Source(String)
+    /// }
+    ///
+    /// let obs = of(&[1, 2, 3])
+    ///     .map_err(|e: &RxError| ServiceError::Source(e.to_string()));
+    ///
+    /// obs.subscribe_error(|e: &ServiceError| println!("{:?}", e));
+    /// ```
+    pub fn map_err<E2: 'a, F>(self, predicate: F) -> Observable<'a, T, E2>
+        where F: FnMut(&E) -> E2 + Clone + Send + Sync + 'a {
+        Observable::new(move |destination: Subscriber<'a, T, E2>| {
+            let map_err_observer = MapErrObserver::new(destination, predicate.clone());
+            let mut subscription = self.subscribe(map_err_observer);
+
+            Unsubscriber::new(move || subscription.unsubscribe())
+        })
+    }
+
+    /// Accumulates the values of an `Observable`, starting from the `seed`, and emits every
+    /// intermediate accumulation. The `accumulator` receives the current accumulation and the
+    /// value, and returns the new accumulation. Every subscription starts from its own copy of
+    /// the `seed`.
+    ///
+    /// ```rust
+    /// use rxrs::operators::of;
+    ///
+    /// // emits 1, 3, 6
+    /// of(&[1, 2, 3])
+    ///     .scan(0, |total, value| total + value)
+    ///     .subscribe_next(|total| println!("{}", total));
+    /// ```
+    pub fn scan<U, F>(self, seed: U, accumulator: F) -> Observable<'a, U, E>
+        where U: Clone + Send + Sync + 'a,
+              F: FnMut(&U, &T) -> U + Clone + Send + Sync + 'a {
+        Observable::new(move |destination: Subscriber<'a, U, E>| {
+            let scan_observer = ScanObserver::new(destination, seed.clone(), accumulator.clone());
+            let mut subscription = self.subscribe(scan_observer);
+
+            Unsubscriber::new(move || subscription.unsubscribe())
+        })
+    }
+
+    /// Accumulates the values of an `Observable` like `scan`, but only emits the final
+    /// accumulation once the source completes. The `seed` is emitted if the source completes
+    /// without any value.
+    ///
+    /// ```rust
+    /// use rxrs::operators::of;
+    ///
+    /// // emits 6
+    /// of(&[1, 2, 3])
+    ///     .reduce(0, |total, value| total + value)
+    ///     .subscribe_next(|total| println!("{}", total));
+    /// ```
+    pub fn reduce<U, F>(self, seed: U, accumulator: F) -> Observable<'a, U, E>
+        where U: Clone + Send + Sync + 'a,
+              F: FnMut(&U, &T) -> U + Clone + Send + Sync + 'a {
+        Observable::new(move |destination: Subscriber<'a, U, E>| {
+            let reduce_observer = ReduceObserver::new(destination, seed.clone(), accumulator.clone());
+            let mut subscription = self.subscribe(reduce_observer);
+
+            Unsubscriber::new(move || subscription.unsubscribe())
+        })
+    }
+
+    /// Repeats the stream of an `Observable`, by subscribing to it again every time it completes,
+    /// until it has been subscribed `count` times. Errors are not repeated.
+    ///
+    /// ```rust
+    /// use rxrs::operators::of;
+    ///
+    /// // emits 1, 2, 1, 2, 1, 2
+    /// of(&[1, 2]).repeat(3)
+    ///     .subscribe_next(|value| println!("{}", value));
+    /// ```
+    pub fn repeat(self, count: u64) -> Observable<'a, T, E> {
+        self.repeat_with(RepeatNotifier::Count(count))
+    }
+
+    /// Repeats the stream of an `Observable` every time it completes, once the observable returned
+    /// by the `notifier` emits a value. The `notifier` is called with the number of completions of
+    /// the source. If its observable completes without emitting any value, the repetition
+    /// completes, and if it emits an error, the error is forwarded.
+    ///
+    /// ```rust
+    /// use rxrs::operators::{empty, just, of};
+    ///
+    /// // repeats the source until it completed 3 times
+    /// of(&[1, 2])
+    ///     .repeat_when(|completions| if completions < 3 { just(true) } else { empty() })
+    ///     .subscribe_next(|value| println!("{}", value));
+    /// ```
+    pub fn repeat_when<U: 'a, F>(self, notifier: F) -> Observable<'a, T, E>
+        where F: Fn(u64) -> Observable<'a, U, E> + Send + Sync + 'a {
+        self.repeat_with(RepeatNotifier::When(Box::new(move |completions| notifier(completions).map(|_| ()))))
+    }
+
+    fn repeat_with(self, notifier: RepeatNotifier<'a, E>) -> Observable<'a, T, E> {
+        let (source, notifier) = (Arc::new(self), Arc::new(notifier));
+
+        Observable::new(move |mut destination: Subscriber<'a, T, E>| {
+            if let RepeatNotifier::Count(0) = *notifier {
+                destination.complete();
+                return Unsubscriber::new(|| {});
+            }
+
+            let state = RepeatState::new(source.clone(), notifier.clone(), destination);
+            state.subscribe_source();
+
+            let mut subscription = state.subscription();
+            Unsubscriber::new(move || subscription.unsubscribe())
+        })
+    }
 }
 
-impl<'a, T: 'a, O: 'a> Observable<'a, T, DelayObserver<T, O>>
-    where O: ObserverLike<Value=T, Error=RxError> {
-    /// Delays the `Observable` stream items by a `value` amount of time (in ms);
+impl<T: Clone + Send + 'static, E: 'static> Observable<'static, T, E> {
+    /// Delays every event of the `Observable` (its values, the error and the complete event) by
+    /// the `delay` amount of time, keeping their relative spacing. The delayed events are emitted
+    /// through the given `scheduler`, and the pending ones are cancelled once unsubscribed.
     ///
     /// ```rust
-    /// use std::time::Instant;
+    /// use std::time::Duration;
     /// use rxrs::operators::of;
+    /// use rxrs::scheduler::TestScheduler;
     ///
+    /// let scheduler = TestScheduler::new();
     /// let delayed = of(&[1, 2, 3])
-    /// 	.delay(10);
+    ///     .delay(Duration::from_millis(10), scheduler.clone());
     ///
-    /// let start = Instant::now();
-    /// delayed.subscribe_next(move |_| {
-    /// 	let diff = start.elapsed().as_millis();
-    /// 	println!("delay: {}", diff);
-    /// });
+    /// let clock = scheduler.clone();
+    /// delayed.subscribe_next(move |value| println!("{} after {:?}", value, clock.elapsed()));
+    ///
+    /// // emits 1, 2 and 3 after 10 ms, without blocking the source
+    /// scheduler.advance_by(Duration::from_millis(10));
     /// ```
-    pub fn delay(self, value: u64) -> Observable<'a, T, O> {
-        Observable::new(move |destination: O| {
-            let delay_observer = DelayObserver::new(destination, value);
+    pub fn delay<S>(self, delay: Duration, scheduler: S) -> Observable<'static, T, E>
+        where E: Clone + Send,
+              S: Scheduler + Clone + 'static {
+        Observable::new(move |destination: Subscriber<'static, T, E>| {
+            let delay_observer = DelayObserver::new(destination, delay, scheduler.clone());
+            let pending = delay_observer.pending();
             let mut subscription = self.subscribe(delay_observer);
 
-            Unsubscriber::new(move || subscription.unsubscribe())
+            Unsubscriber::new(move || {
+                subscription.unsubscribe();
+                pending.cancel();
+            })
+        })
+    }
+
+    /// Delays every value of the `Observable` until the observable returned by the `selector`
+    /// for the value emits. A value whose delay completes without emitting is dropped, and the
+    /// complete event is emitted once every pending value has been delivered. Errors are not
+    /// delayed.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rxrs::operators::{of, timer};
+    /// use rxrs::scheduler::TestScheduler;
+    ///
+    /// let scheduler = TestScheduler::new();
+    /// let delay_scheduler = scheduler.clone();
+    ///
+    /// // emits 1 after 10 ms, 2 after 20 ms and 3 after 30 ms
+    /// of(&[3, 1, 2])
+    ///     .delay_when(move |value| timer(Duration::from_millis(value * 10), delay_scheduler.clone()))
+    ///     .subscribe_next(|value| println!("{}", value));
+    ///
+    /// scheduler.flush();
+    /// ```
+    pub fn delay_when<U: 'static, F>(self, selector: F) -> Observable<'static, T, E>
+        where F: FnMut(&T) -> Observable<'static, U, E> + Clone + Send + Sync + 'static {
+        Observable::new(move |destination: Subscriber<'static, T, E>| {
+            let delay_when_observer = DelayWhenObserver::new(destination, selector.clone());
+            let mut delays = delay_when_observer.subscription();
+            let mut subscription = self.subscribe(delay_when_observer);
+
+            Unsubscriber::new(move || {
+                subscription.unsubscribe();
+                delays.unsubscribe();
+            })
         })
     }
 }
 
-impl<'a, T> Observable<'a, T, Observer<T>> {
+impl<'a, T: 'a, E: 'a> Observable<'a, T, E> {
     /// `subscribe_next` subscribes to the event stream of next values from an `Observable` instance.
     pub fn subscribe_next<N>(&self, next: N) -> Subscription
         where N: Fn(&T) + 'static + Send {
@@ -143,8 +332,8 @@ impl<'a, T> Observable<'a, T, Observer<T>> {
     }
 
     /// `subscribe_error` subscribes to the event stream of errors from an `Observable` instance.
-    pub fn subscribe_error<E>(&self, error: E) -> Subscription
-        where E: Fn(&RxError) + 'static + Send {
+    pub fn subscribe_error<EF>(&self, error: EF) -> Subscription
+        where EF: Fn(&E) + 'static + Send {
         self.subscribe_all(|_| {}, error, || {})
     }
 
@@ -156,12 +345,12 @@ impl<'a, T> Observable<'a, T, Observer<T>> {
 
     /// `subscribe_all` subscribes to the event stream of an `Observable` instance, and provides
     /// a `next`, `error` and `complete` function handlers for the different type of events.
-    pub fn subscribe_all<N, E, C>(&self, next: N, error: E, complete: C) -> Subscription
+    pub fn subscribe_all<N, EF, C>(&self, next: N, error: EF, complete: C) -> Subscription
         where N: Fn(&T) + 'static + Send,
-              E: Fn(&RxError) + 'static + Send,
+              EF: Fn(&E) + 'static + Send,
               C: Fn() + 'static + Send {
         // generate a subscriber from the input events
-        let subscriber = Observer::<T>::new(
+        let subscriber = Observer::<T, E>::new(
             Box::new(next), Box::new(error), Box::new(complete),
         );
 
@@ -171,26 +360,36 @@ impl<'a, T> Observable<'a, T, Observer<T>> {
 
 /// `ObservableLike` is a trait definition for data structures that implement the `Observable`
 /// logic, and thus, can be subscribed to. Logically, `Observable` is an `ObservableLike` struct,
-/// but also `Subject` can be also subscribed to.
+/// but also `Subject` can be also subscribed to. The lifetime `'a` bounds the observers that
+/// can subscribe to the instance.
 pub trait ObservableLike<'a> {
-    type Observer: ObserverLike;
+    type Value;
+    type Error;
     type Subscription: Unsubscribable;
 
     /// `subscribe` executes the `Observable` instance and returns the generated event values to
     /// the `Observer` input parameter. A `Subscription` instance is returned, that can be
     /// unsubscribed, and no further events would be listened to.
-    fn subscribe(&'a self, observer: Self::Observer) -> Self::Subscription;
+    fn subscribe<O>(&self, observer: O) -> Self::Subscription
+        where O: ObserverLike<Value=Self::Value, Error=Self::Error> + Send + 'a;
 }
 
-impl<'a, T, O> ObservableLike<'a> for Observable<'a, T, O>
-    where O: ObserverLike<Value=T, Error=RxError> {
-    type Observer = O;
+impl<'a, T, E> ObservableLike<'a> for Observable<'a, T, E> {
+    type Value = T;
+    type Error = E;
     type Subscription = Subscription;
 
     /// Subscribes to the event stream of the `Observable` instance. The `Subscriber` function
     /// provided when creating the `Observable` instance is called, and a `Subscription` is created.
-    fn subscribe(&self, observer: Self::Observer) -> Self::Subscription {
-        let unsubscriber = self.observer_fn.call(observer);
-        Subscription::new(unsubscriber)
+    /// The `observer` is wrapped in a `SafeObserver`, so that it receives no event after an error
+    /// or the completion, which also unsubscribe the `Subscription`.
+    fn subscribe<O>(&self, observer: O) -> Subscription
+        where O: ObserverLike<Value=T, Error=E> + Send + 'a {
+        let subscription = Subscription::empty();
+
+        let mut unsubscriber = self.observer_fn.call(Subscriber::new(SafeObserver::new(observer, subscription.clone())));
+        subscription.add_teardown(move || unsubscriber.call());
+
+        subscription
     }
 }
@@ -7,19 +7,25 @@ pub trait ObserverLike {
     fn next(&self, value: &Self::Value) -> ();
     fn error(&self, e: &Self::Error) -> ();
     fn complete(&mut self) -> ();
+
+    /// Returns `true` if the observer doesn't accept any further event, so that a source can
+    /// stop producing values (e.g. a synchronous loop over a collection).
+    fn is_closed(&self) -> bool {
+        false
+    }
 }
 
-pub struct Observer<T> {
+pub struct Observer<T, E = RxError> {
     next_fn: Box<dyn Fn(&T) + Send>,
-    error_fn: Box<dyn Fn(&RxError) + Send>,
+    error_fn: Box<dyn Fn(&E) + Send>,
     complete_fn: Box<dyn Fn() + Send>,
     pub stopped: bool,
 }
 
-impl<T> Observer<T> {
-    pub fn new<N, E, C>(next: N, error: E, complete: C) -> Observer<T>
+impl<T, E> Observer<T, E> {
+    pub fn new<N, EF, C>(next: N, error: EF, complete: C) -> Observer<T, E>
         where N: Fn(&T) + 'static + Send,
-              E: Fn(&RxError) + 'static + Send,
+              EF: Fn(&E) + 'static + Send,
               C: Fn() + 'static + Send {
         Observer {
             next_fn: Box::new(next),
@@ -30,9 +36,9 @@ impl<T> Observer<T> {
     }
 }
 
-impl<T> ObserverLike for Observer<T> {
+impl<T, E> ObserverLike for Observer<T, E> {
     type Value = T;
-    type Error = RxError;
+    type Error = E;
 
     fn next(&self, t: &Self::Value) {
         if !self.stopped {
@@ -50,4 +56,8 @@ impl<T> ObserverLike for Observer<T> {
             (self.complete_fn)();
         }
     }
+
+    fn is_closed(&self) -> bool {
+        self.stopped
+    }
 }
\ No newline at end of file
@@ -1,35 +1,204 @@
-use std::thread::sleep;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use crate::error::RxError;
 use crate::observer::ObserverLike;
+use crate::scheduler::{ScheduleHandle, Scheduler, Task};
 
-pub struct DelayObserver<T, D> where D: ObserverLike<Value=T, Error=RxError> {
-    delay: u64,
-    destination: D,
+/// `PendingDeliveries` tracks the notifications scheduled by a `DelayObserver` that weren't
+/// delivered yet, so that they can be cancelled once the subscription is unsubscribed.
+#[derive(Clone, Default)]
+pub struct PendingDeliveries {
+    state: Arc<Mutex<PendingState>>,
 }
 
-impl<T, D> DelayObserver<T, D> where D: ObserverLike<Value=T, Error=RxError> {
-    pub fn new(destination: D, delay: u64) -> DelayObserver<T, D> {
-        DelayObserver { destination, delay }
+#[derive(Default)]
+struct PendingState {
+    next_key: u64,
+    // the handle is `None` while its task is being scheduled
+    handles: BTreeMap<u64, Option<ScheduleHandle>>,
+    cancelled: bool,
+}
+
+impl PendingDeliveries {
+    /// Schedules the `task` after the `delay`, unless the deliveries were cancelled.
+    fn schedule<S: Scheduler>(&self, scheduler: &S, delay: Duration, task: Task) {
+        let key = {
+            let mut state = self.state.lock().unwrap();
+            if state.cancelled {
+                return;
+            }
+
+            let key = state.next_key;
+            state.next_key += 1;
+            state.handles.insert(key, None);
+            key
+        };
+
+        let pending = self.state.clone();
+        let handle = scheduler.schedule_after(delay, Box::new(move || {
+            pending.lock().unwrap().handles.remove(&key);
+            task();
+        }));
+
+        // the task might already be delivered (e.g. by an `ImmediateScheduler`)
+        let mut state = self.state.lock().unwrap();
+        if state.cancelled {
+            handle.cancel();
+        } else if let Some(slot) = state.handles.get_mut(&key) {
+            *slot = Some(handle);
+        }
     }
+
+    /// Cancels every pending delivery, and any further one.
+    pub fn cancel(&self) {
+        let handles = {
+            let mut state = self.state.lock().unwrap();
+            state.cancelled = true;
+            std::mem::take(&mut state.handles)
+        };
+
+        handles.values().flatten().for_each(ScheduleHandle::cancel);
+    }
+}
+
+/// `DelayedEvent` is a notification waiting for its delay to elapse.
+enum DelayedEvent<T, E> {
+    Next(T),
+    Error(E),
+    Complete,
 }
 
-impl<T, D> ObserverLike for DelayObserver<T, D>
-    where D: ObserverLike<Value=T, Error=RxError> {
+/// Delayed notifications, with the key of their order of arrival.
+type QueuedEvents<T, E> = VecDeque<(u64, DelayedEvent<T, E>)>;
+
+/// `QueueState` holds the delayed notifications of a `DelayObserver` in the order they were
+/// received, and the key of the last notification which is due.
+struct QueueState<T, E> {
+    next_key: u64,
+    due: Option<u64>,
+    emitting: bool,
+    events: QueuedEvents<T, E>,
+}
+
+/// `DelayQueue` delivers the delayed notifications of a `DelayObserver`. Every notification is
+/// delivered together with the earlier ones still queued, so that the order is kept even if the
+/// scheduler runs their tasks out of order (e.g. on several threads).
+///
+/// The notifications are delivered by a single task at a time, without holding the lock of the
+/// queue, so that the destination can cancel the other tasks (and wait for them) meanwhile.
+struct DelayQueue<D: ObserverLike> {
+    state: Mutex<QueueState<D::Value, D::Error>>,
+    destination: Mutex<D>,
+    closed: AtomicBool,
+}
+
+impl<D: ObserverLike> DelayQueue<D> {
+    fn push(&self, event: DelayedEvent<D::Value, D::Error>) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let key = state.next_key;
+        state.next_key += 1;
+        state.events.push_back((key, event));
+        key
+    }
+
+    /// Delivers, in order, every queued notification up to the one of the given `key`. The
+    /// earlier notifications were due before it. If another task is delivering, it delivers
+    /// them instead.
+    fn deliver_until(&self, key: u64) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.due = state.due.max(Some(key));
+            if std::mem::replace(&mut state.emitting, true) {
+                return;
+            }
+        }
+
+        loop {
+            let event = {
+                let mut state = self.state.lock().unwrap();
+                let due = state.due;
+                match state.events.front() {
+                    Some((queued, _)) if Some(*queued) <= due => state.events.pop_front(),
+                    _ => {
+                        state.emitting = false;
+                        return;
+                    }
+                }
+            };
+
+            let mut destination = self.destination.lock().unwrap();
+            match event {
+                Some((_, DelayedEvent::Next(value))) => destination.next(&value),
+                Some((_, DelayedEvent::Error(e))) => destination.error(&e),
+                Some((_, DelayedEvent::Complete)) => destination.complete(),
+                None => {}
+            }
+            self.closed.store(destination.is_closed(), Ordering::SeqCst);
+        }
+    }
+}
+
+pub struct DelayObserver<T, D, S> where D: ObserverLike<Value=T>, S: Scheduler {
+    delay: Duration,
+    queue: Arc<DelayQueue<D>>,
+    scheduler: S,
+    pending: PendingDeliveries,
+}
+
+impl<T, D, S> DelayObserver<T, D, S> where D: ObserverLike<Value=T>, S: Scheduler {
+    pub fn new(destination: D, delay: Duration, scheduler: S) -> DelayObserver<T, D, S> {
+        let state = QueueState { next_key: 0, due: None, emitting: false, events: VecDeque::new() };
+        let closed = AtomicBool::new(destination.is_closed());
+        DelayObserver {
+            delay,
+            queue: Arc::new(DelayQueue { state: Mutex::new(state), destination: Mutex::new(destination), closed }),
+            scheduler,
+            pending: PendingDeliveries::default(),
+        }
+    }
+
+    /// Returns the deliveries scheduled by this observer.
+    pub fn pending(&self) -> PendingDeliveries {
+        self.pending.clone()
+    }
+}
+
+impl<T, D, S> DelayObserver<T, D, S>
+    where T: Send + 'static,
+          D: ObserverLike<Value=T> + Send + 'static,
+          D::Error: Send,
+          S: Scheduler {
+    fn schedule(&self, event: DelayedEvent<T, D::Error>) {
+        let key = self.queue.push(event);
+
+        let queue = self.queue.clone();
+        self.pending.schedule(&self.scheduler, self.delay, Box::new(move || queue.deliver_until(key)));
+    }
+}
+
+impl<T, D, S> ObserverLike for DelayObserver<T, D, S>
+    where T: Clone + Send + 'static,
+          D: ObserverLike<Value=T> + Send + 'static,
+          D::Error: Clone + Send,
+          S: Scheduler {
     type Value = T;
-    type Error = RxError;
+    type Error = D::Error;
 
     fn next(&self, value: &Self::Value) {
-        sleep(Duration::from_millis(self.delay));
-        self.destination.next(value)
+        self.schedule(DelayedEvent::Next(value.clone()));
     }
 
     fn error(&self, e: &Self::Error) {
-        self.destination.error(e);
+        self.schedule(DelayedEvent::Error(e.clone()));
     }
 
     fn complete(&mut self) {
-        self.destination.complete();
+        self.schedule(DelayedEvent::Complete);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.closed.load(Ordering::SeqCst)
     }
 }
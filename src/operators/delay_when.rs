@@ -0,0 +1,148 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::observable::{Observable, ObservableLike};
+use crate::observer::ObserverLike;
+use crate::subscription::{Subscription, Unsubscribable};
+
+/// `DelayState` is shared by a `DelayWhenObserver` and the observers of its delays.
+struct DelayState<D> {
+    destination: D,
+    active: usize,
+    completed: bool,
+}
+
+impl<D: ObserverLike> DelayState<D> {
+    /// Completes the destination once the source completed and every delay finished.
+    fn finish_delay(&mut self) {
+        self.active -= 1;
+        if self.completed && self.active == 0 {
+            self.destination.complete();
+        }
+    }
+}
+
+pub struct DelayWhenObserver<T, U, D, F>
+    where D: ObserverLike<Value=T>,
+          F: FnMut(&T) -> Observable<'static, U, D::Error> {
+    state: Arc<Mutex<DelayState<D>>>,
+    selector: RefCell<F>,
+    subscription: Subscription,
+}
+
+impl<T, U, D, F> DelayWhenObserver<T, U, D, F>
+    where D: ObserverLike<Value=T>,
+          F: FnMut(&T) -> Observable<'static, U, D::Error> {
+    pub fn new(destination: D, selector: F) -> DelayWhenObserver<T, U, D, F> {
+        DelayWhenObserver {
+            state: Arc::new(Mutex::new(DelayState { destination, active: 0, completed: false })),
+            selector: RefCell::new(selector),
+            subscription: Subscription::empty(),
+        }
+    }
+
+    /// Returns the `Subscription` grouping the subscriptions to the pending delays.
+    pub fn subscription(&self) -> Subscription {
+        self.subscription.clone()
+    }
+}
+
+impl<T, U, D, F> ObserverLike for DelayWhenObserver<T, U, D, F>
+    where T: Clone + Send + 'static,
+          U: 'static,
+          D: ObserverLike<Value=T> + Send + 'static,
+          D::Error: 'static,
+          F: FnMut(&T) -> Observable<'static, U, D::Error> {
+    type Value = T;
+    type Error = D::Error;
+
+    fn next(&self, value: &Self::Value) {
+        let delay = (self.selector.borrow_mut())(value);
+        self.state.lock().unwrap().active += 1;
+
+        let delivered = Arc::new(AtomicBool::new(false));
+        let observer = DurationObserver {
+            value: value.clone(),
+            state: self.state.clone(),
+            delivered: delivered.clone(),
+            subscription: Arc::new(Mutex::new(None)),
+            _value: PhantomData,
+        };
+        let slot = observer.subscription.clone();
+
+        let mut child = delay.subscribe(observer);
+        slot.lock().unwrap().replace(child.clone());
+
+        if delivered.load(Ordering::SeqCst) {
+            child.unsubscribe();
+        } else {
+            self.subscription.add(child);
+        }
+    }
+
+    fn error(&self, e: &Self::Error) {
+        self.state.lock().unwrap().destination.error(e);
+    }
+
+    fn complete(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.completed = true;
+        if state.active == 0 {
+            state.destination.complete();
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().destination.is_closed()
+    }
+}
+
+/// `DurationObserver` observes the delay of a single value, which is delivered on the first
+/// event of the delay. A delay completing without any value drops the delayed value.
+struct DurationObserver<T, U, D> {
+    value: T,
+    state: Arc<Mutex<DelayState<D>>>,
+    delivered: Arc<AtomicBool>,
+    subscription: Arc<Mutex<Option<Subscription>>>,
+    _value: PhantomData<fn(&U)>,
+}
+
+impl<T, U, D> ObserverLike for DurationObserver<T, U, D> where D: ObserverLike<Value=T> {
+    type Value = U;
+    type Error = D::Error;
+
+    fn next(&self, _: &Self::Value) {
+        if self.delivered.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        {
+            let mut state = self.state.lock().unwrap();
+            state.destination.next(&self.value);
+            state.finish_delay();
+        }
+
+        let subscription = self.subscription.lock().unwrap().take();
+        if let Some(mut subscription) = subscription {
+            subscription.unsubscribe();
+        }
+    }
+
+    fn error(&self, e: &Self::Error) {
+        if !self.delivered.swap(true, Ordering::SeqCst) {
+            self.state.lock().unwrap().destination.error(e);
+        }
+    }
+
+    fn complete(&mut self) {
+        if !self.delivered.swap(true, Ordering::SeqCst) {
+            self.state.lock().unwrap().finish_delay();
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.delivered.load(Ordering::SeqCst)
+    }
+}
@@ -1,29 +1,32 @@
-use crate::error::RxError;
-use crate::observer::ObserverLike;
+use std::cell::RefCell;
+use std::marker::PhantomData;
 
-pub type FilterPredicate<T> = fn(&T) -> bool;
+use crate::observer::ObserverLike;
 
-pub struct FilterObserver<T, D> where D: ObserverLike<Value=T, Error=RxError> {
+pub struct FilterObserver<T, D, F>
+    where D: ObserverLike<Value=T>,
+          F: FnMut(&T) -> bool {
     destination: D,
-    predicate: FilterPredicate<T>,
+    predicate: RefCell<F>,
+    _value: PhantomData<fn(&T)>,
 }
 
-impl<T, D> FilterObserver<T, D> where D: ObserverLike<Value=T, Error=RxError> {
-    pub fn new(
-		destination: D,
-		predicate: FilterPredicate<T>,
-	) -> FilterObserver<T, D> {
-        FilterObserver { destination, predicate }
+impl<T, D, F> FilterObserver<T, D, F>
+    where D: ObserverLike<Value=T>,
+          F: FnMut(&T) -> bool {
+    pub fn new(destination: D, predicate: F) -> FilterObserver<T, D, F> {
+        FilterObserver { destination, predicate: RefCell::new(predicate), _value: PhantomData }
     }
 }
 
-impl<T, D> ObserverLike for FilterObserver<T, D>
-    where D: ObserverLike<Value=T, Error=RxError> {
+impl<T, D, F> ObserverLike for FilterObserver<T, D, F>
+    where D: ObserverLike<Value=T>,
+          F: FnMut(&T) -> bool {
     type Value = T;
-    type Error = RxError;
+    type Error = D::Error;
 
     fn next(&self, value: &Self::Value) {
-        if (self.predicate)(value) {
+        if (self.predicate.borrow_mut())(value) {
             self.destination.next(value);
         }
     }
@@ -35,4 +38,8 @@ impl<T, D> ObserverLike for FilterObserver<T, D>
     fn complete(&mut self) {
         self.destination.complete();
     }
+
+    fn is_closed(&self) -> bool {
+        self.destination.is_closed()
+    }
 }
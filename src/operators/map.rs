@@ -1,29 +1,32 @@
-use crate::error::RxError;
-use crate::observer::ObserverLike;
+use std::cell::RefCell;
+use std::marker::PhantomData;
 
-pub type MapPredicate<T, U> = fn(&T) -> U;
+use crate::observer::ObserverLike;
 
-pub struct MapObserver<T, U, D> where D: ObserverLike<Value=U, Error=RxError> {
+pub struct MapObserver<T, U, D, F>
+    where D: ObserverLike<Value=U>,
+          F: FnMut(&T) -> U {
     destination: D,
-    predicate: MapPredicate<T, U>,
+    predicate: RefCell<F>,
+    _value: PhantomData<fn(&T) -> U>,
 }
 
-impl<T, U, D> MapObserver<T, U, D> where D: ObserverLike<Value=U, Error=RxError> {
-    pub fn new(
-		destination: D,
-		predicate: MapPredicate<T, U>,
-	) -> MapObserver<T, U, D> {
-        MapObserver { destination, predicate }
+impl<T, U, D, F> MapObserver<T, U, D, F>
+    where D: ObserverLike<Value=U>,
+          F: FnMut(&T) -> U {
+    pub fn new(destination: D, predicate: F) -> MapObserver<T, U, D, F> {
+        MapObserver { destination, predicate: RefCell::new(predicate), _value: PhantomData }
     }
 }
 
-impl<T, U, D> ObserverLike for MapObserver<T, U, D>
-    where D: ObserverLike<Value=U, Error=RxError> {
+impl<T, U, D, F> ObserverLike for MapObserver<T, U, D, F>
+    where D: ObserverLike<Value=U>,
+          F: FnMut(&T) -> U {
     type Value = T;
-    type Error = RxError;
+    type Error = D::Error;
 
     fn next(&self, value: &Self::Value) {
-        let result = (self.predicate)(value);
+        let result = (self.predicate.borrow_mut())(value);
         self.destination.next(&result);
     }
 
@@ -34,4 +37,8 @@ impl<T, U, D> ObserverLike for MapObserver<T, U, D>
     fn complete(&mut self) {
         self.destination.complete();
     }
+
+    fn is_closed(&self) -> bool {
+        self.destination.is_closed()
+    }
 }
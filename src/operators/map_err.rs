@@ -0,0 +1,44 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use crate::observer::ObserverLike;
+
+pub struct MapErrObserver<T, E, D, F>
+    where D: ObserverLike<Value=T>,
+          F: FnMut(&E) -> D::Error {
+    destination: D,
+    predicate: RefCell<F>,
+    _error: PhantomData<fn(&E)>,
+}
+
+impl<T, E, D, F> MapErrObserver<T, E, D, F>
+    where D: ObserverLike<Value=T>,
+          F: FnMut(&E) -> D::Error {
+    pub fn new(destination: D, predicate: F) -> MapErrObserver<T, E, D, F> {
+        MapErrObserver { destination, predicate: RefCell::new(predicate), _error: PhantomData }
+    }
+}
+
+impl<T, E, D, F> ObserverLike for MapErrObserver<T, E, D, F>
+    where D: ObserverLike<Value=T>,
+          F: FnMut(&E) -> D::Error {
+    type Value = T;
+    type Error = E;
+
+    fn next(&self, value: &Self::Value) {
+        self.destination.next(value);
+    }
+
+    fn error(&self, e: &Self::Error) {
+        let result = (self.predicate.borrow_mut())(e);
+        self.destination.error(&result);
+    }
+
+    fn complete(&mut self) {
+        self.destination.complete();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.destination.is_closed()
+    }
+}
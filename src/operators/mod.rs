@@ -1,14 +1,20 @@
-use std::sync::mpsc::channel;
-use std::thread::{sleep, spawn};
+use std::convert::TryFrom;
 use std::time::Duration;
 
-use crate::error::RxError;
-use crate::observable::{Observable, Unsubscriber};
+use crate::observable::{Observable, ObservableLike, Unsubscriber};
 use crate::observer::ObserverLike;
+use crate::scheduler::Scheduler;
+use crate::subscriber::Subscriber;
+use crate::subscription::Unsubscribable;
 
 pub(crate) mod delay;
+pub(crate) mod delay_when;
 pub(crate) mod filter;
 pub(crate) mod map;
+pub(crate) mod map_err;
+pub(crate) mod reduce;
+pub(crate) mod repeat;
+pub(crate) mod scan;
 
 /// `of` creates a finite number of observables with a defined value.
 /// ```rust
@@ -21,10 +27,12 @@ pub(crate) mod map;
 ///   || println!("completed")
 /// );
 /// ```
-pub fn of<T, O>(values: &[T]) -> Observable<T, O>
-    where O: ObserverLike<Value=T, Error=RxError> {
-    Observable::new(move |mut subscriber: O| {
+pub fn of<'a, T: Sync>(values: &'a [T]) -> Observable<'a, T> {
+    Observable::new(move |mut subscriber: Subscriber<'a, T>| {
         for value in values {
+            if subscriber.is_closed() {
+                return Unsubscriber::new(|| {});
+            }
             subscriber.next(value);
         }
         subscriber.complete();
@@ -33,17 +41,290 @@ pub fn of<T, O>(values: &[T]) -> Observable<T, O>
     })
 }
 
-/// `interval` creates an infinite observable that emits sequential numbers every specified
-/// interval of time.
+/// `from_iter` creates an observable emitting the items of an iterable, and completing
+/// afterwards. The observable owns the iterable, which is cloned for every subscription, so that
+/// it can be returned or stored without borrowing any local data.
+/// ```rust
+/// use rxrs::operators::from_iter;
+///
+/// from_iter((1..4).map(|value| value * 10))
+///     .subscribe_next(|value| println!("{}", value));
+/// ```
+pub fn from_iter<I>(iter: I) -> Observable<'static, I::Item>
+    where I: IntoIterator + Clone + Send + Sync + 'static {
+    Observable::new(move |mut subscriber: Subscriber<'static, I::Item>| {
+        for value in iter.clone() {
+            if subscriber.is_closed() {
+                return Unsubscriber::new(|| {});
+            }
+            subscriber.next(&value);
+        }
+        subscriber.complete();
+
+        Unsubscriber::new(|| {})
+    })
+}
+
+/// `from_vec` creates an observable emitting the values of a vector, and completing afterwards.
+/// ```rust
+/// use rxrs::operators::from_vec;
+///
+/// from_vec(vec![String::from("a"), String::from("b")])
+///     .subscribe_next(|value| println!("{}", value));
+/// ```
+pub fn from_vec<T: Send + Sync + 'static>(values: Vec<T>) -> Observable<'static, T> {
+    Observable::new(move |mut subscriber: Subscriber<'static, T>| {
+        for value in &values {
+            if subscriber.is_closed() {
+                return Unsubscriber::new(|| {});
+            }
+            subscriber.next(value);
+        }
+        subscriber.complete();
+
+        Unsubscriber::new(|| {})
+    })
+}
+
+/// `just` creates an observable emitting a single `value`, and completing afterwards.
+/// ```rust
+/// use rxrs::operators::just;
+///
+/// just(42).subscribe_next(|value| println!("{}", value));
+/// ```
+pub fn just<T: Send + Sync + 'static>(value: T) -> Observable<'static, T> {
+    Observable::new(move |mut subscriber: Subscriber<'static, T>| {
+        subscriber.next(&value);
+        subscriber.complete();
+
+        Unsubscriber::new(|| {})
+    })
+}
+
+/// `range` creates an observable emitting `count` sequential numbers from `start`, and completing
+/// afterwards. The sequence stops early at `i64::MAX`, instead of overflowing.
+/// ```rust
+/// use rxrs::operators::range;
+///
+/// range(1, 3).subscribe_next(|value| println!("{}", value));
+/// ```
+pub fn range(start: i64, count: u64) -> Observable<'static, i64> {
+    from_iter((0..count).map_while(move |offset| {
+        i64::try_from(offset).ok().and_then(|offset| start.checked_add(offset))
+    }))
+}
+
+/// `empty` creates an observable completing right away, without emitting any value.
+/// ```rust
+/// use rxrs::operators::empty;
+///
+/// empty::<i32>().subscribe_complete(|| println!("completed"));
+/// ```
+pub fn empty<T: 'static>() -> Observable<'static, T> {
+    empty_with_error()
+}
+
+/// `empty_with_error` is `empty`, for an observable whose error type is `E`.
+/// ```rust
+/// use rxrs::operators::empty_with_error;
+///
+/// empty_with_error::<i32, String>().subscribe_complete(|| println!("completed"));
+/// ```
+pub fn empty_with_error<T: 'static, E: 'static>() -> Observable<'static, T, E> {
+    Observable::new(|mut subscriber: Subscriber<'static, T, E>| {
+        subscriber.complete();
+
+        Unsubscriber::new(|| {})
+    })
+}
+
+/// `never` creates an observable that never emits any event.
+pub fn never<T: 'static>() -> Observable<'static, T> {
+    never_with_error()
+}
+
+/// `never_with_error` is `never`, for an observable whose error type is `E`.
+pub fn never_with_error<T: 'static, E: 'static>() -> Observable<'static, T, E> {
+    Observable::new(|_: Subscriber<'static, T, E>| Unsubscriber::new(|| {}))
+}
+
+/// `throw_error` creates an observable emitting the `error` right away, without any value.
+/// ```rust
+/// use rxrs::error::RxError;
+/// use rxrs::operators::throw_error;
+///
+/// throw_error::<i32, _>(RxError::Timeout).subscribe_error(|e| println!("{}", e));
+/// ```
+pub fn throw_error<T: 'static, E: Send + Sync + 'static>(error: E) -> Observable<'static, T, E> {
+    Observable::new(move |subscriber: Subscriber<'static, T, E>| {
+        subscriber.error(&error);
+
+        Unsubscriber::new(|| {})
+    })
+}
+
+/// `defer` creates an observable calling the `factory` for every subscription, and subscribing
+/// to the observable it returns. This way, the source is only created (and its values evaluated)
+/// once subscribed.
+/// ```rust
+/// use std::time::Instant;
+/// use rxrs::operators::{defer, just};
+///
+/// let now = defer(|| just(Instant::now()));
+/// now.subscribe_next(|instant| println!("{:?}", instant));
+/// ```
+pub fn defer<'a, T: 'a, E: 'a, F>(factory: F) -> Observable<'a, T, E>
+    where F: Fn() -> Observable<'a, T, E> + Send + Sync + 'a {
+    Observable::new(move |subscriber: Subscriber<'a, T, E>| {
+        let mut subscription = factory().subscribe(subscriber);
+        Unsubscriber::new(move || subscription.unsubscribe())
+    })
+}
+
+/// `generate` creates an observable from a loop: starting with the `initial` state, it emits every
+/// state while the `condition` holds, and computes the following state with `iterate`. Every
+/// iteration is executed through the given `scheduler`, and the observable completes once the
+/// `condition` doesn't hold anymore.
+/// ```rust
+/// use rxrs::operators::generate;
+/// use rxrs::scheduler::ImmediateScheduler;
+///
+/// // emits 1, 2, 4, 8
+/// generate(1, |value| *value < 10, |value| value * 2, ImmediateScheduler::new())
+///     .subscribe_next(|value| println!("{}", value));
+/// ```
+pub fn generate<T, C, I, S>(initial: T, condition: C, iterate: I, scheduler: S) -> Observable<'static, T>
+    where T: Clone + Send + Sync + 'static,
+          C: FnMut(&T) -> bool + Clone + Send + Sync + 'static,
+          I: FnMut(&T) -> T + Clone + Send + Sync + 'static,
+          S: Scheduler + 'static {
+    Observable::new(move |mut subscriber: Subscriber<'static, T>| {
+        let (mut state, mut condition, mut iterate) = (initial.clone(), condition.clone(), iterate.clone());
+
+        let handle = scheduler.schedule_periodic(Duration::ZERO, Duration::ZERO, Box::new(move |handle| {
+            if subscriber.is_closed() {
+                handle.cancel();
+            } else if condition(&state) {
+                subscriber.next(&state);
+                state = iterate(&state);
+            } else {
+                subscriber.complete();
+                handle.cancel();
+            }
+        }));
+
+        Unsubscriber::new(move || handle.cancel())
+    })
+}
+
+/// `timer` creates an observable that emits `0` once the `due` time elapsed, and completes
+/// afterwards. The value is emitted through the given `scheduler`.
+/// ```rust
+/// use std::time::Duration;
+/// use rxrs::operators::timer;
+/// use rxrs::scheduler::TestScheduler;
+///
+/// let scheduler = TestScheduler::new();
+/// timer(Duration::from_millis(100), scheduler.clone())
+///     .subscribe_next(|value| println!("{}", value));
+///
+/// scheduler.advance_by(Duration::from_millis(100));
+/// ```
+pub fn timer<S>(due: Duration, scheduler: S) -> Observable<'static, u64>
+    where S: Scheduler + 'static {
+    timer_with_error(due, scheduler)
+}
+
+/// `timer_with_error` is `timer`, for an observable whose error type is `E`, e.g. to delay the
+/// values of an observable failing with `E`.
+/// ```rust
+/// use std::time::Duration;
+/// use rxrs::operators::{of, timer_with_error};
+/// use rxrs::scheduler::TestScheduler;
+///
+/// let scheduler = TestScheduler::new();
+/// let delays = scheduler.clone();
+/// of(&[1, 2])
+///     .map_err(|e| e.to_string())
+///     .delay_when(move |_| timer_with_error(Duration::from_millis(100), delays.clone()))
+///     .subscribe_next(|value| println!("{}", value));
+///
+/// scheduler.advance_by(Duration::from_millis(100));
+/// ```
+pub fn timer_with_error<S, E>(due: Duration, scheduler: S) -> Observable<'static, u64, E>
+    where S: Scheduler + 'static,
+          E: 'static {
+    Observable::new(move |subscriber: Subscriber<'static, u64, E>| {
+        let mut subscriber = Some(subscriber);
+
+        let handle = scheduler.schedule_after(due, Box::new(move || {
+            if let Some(mut subscriber) = subscriber.take() {
+                subscriber.next(&0);
+                subscriber.complete();
+            }
+        }));
+
+        Unsubscriber::new(move || handle.cancel())
+    })
+}
+
+/// `timer_with_period` creates an infinite observable that emits `0` once the `due` time
+/// elapsed, and then sequential numbers every `period` of time. The values are emitted through
+/// the given `scheduler`.
+/// ```rust
+/// use std::time::Duration;
+/// use rxrs::operators::timer_with_period;
+/// use rxrs::scheduler::TestScheduler;
+///
+/// let scheduler = TestScheduler::new();
+/// timer_with_period(Duration::from_millis(100), Duration::from_millis(10), scheduler.clone())
+///     .subscribe_next(|value| println!("{}", value));
+///
+/// // emits 0, 1 and 2
+/// scheduler.advance_by(Duration::from_millis(120));
+/// ```
+pub fn timer_with_period<S>(due: Duration, period: Duration, scheduler: S) -> Observable<'static, u64>
+    where S: Scheduler + 'static {
+    timer_with_period_with_error(due, period, scheduler)
+}
+
+/// `timer_with_period_with_error` is `timer_with_period`, for an observable whose error type is
+/// `E`.
+pub fn timer_with_period_with_error<S, E>(due: Duration, period: Duration, scheduler: S) -> Observable<'static, u64, E>
+    where S: Scheduler + 'static,
+          E: 'static {
+    Observable::new(move |subscriber: Subscriber<'static, u64, E>| {
+        let mut count = 0;
+
+        let handle = scheduler.schedule_periodic(due, period, Box::new(move |handle| {
+            if subscriber.is_closed() {
+                handle.cancel();
+                return;
+            }
+
+            subscriber.next(&count);
+            count += 1;
+        }));
+
+        Unsubscriber::new(move || handle.cancel())
+    })
+}
+
+/// `interval` creates an infinite observable that emits sequential numbers every `period` of
+/// time, starting after the first period. The values are emitted through the given `scheduler`,
+/// against absolute deadlines: the periods don't drift by the time spent handling the values.
+/// Once unsubscribed, no further value is emitted, and the scheduled task is cancelled (a
+/// `NewThreadScheduler::join_on_cancel` scheduler also waits for its thread to finish).
 /// ```rust
 /// use std::thread;
 /// use std::time::Duration;
 /// use rxrs::observable::ObservableLike;
 /// use rxrs::subscription::Unsubscribable;
 /// use rxrs::operators::interval;
+/// use rxrs::scheduler::NewThreadScheduler;
 ///
 ///
-/// let mut subscription = interval(1).subscribe_all(
+/// let mut subscription = interval(Duration::from_millis(1), NewThreadScheduler::new()).subscribe_all(
 ///   |value| println!("{}", value),
 ///   |error| println!("{}", error),
 ///   || println!("completed")
@@ -52,28 +333,14 @@ pub fn of<T, O>(values: &[T]) -> Observable<T, O>
 /// thread::sleep(Duration::from_millis(5));
 /// subscription.unsubscribe();
 /// ```
-pub fn interval<'a, O>(interval_time: u64) -> Observable<'a, u64, O>
-    where O: ObserverLike<Value=u64, Error=RxError> + Send + 'static {
-    let observer = move |subscriber: O| {
-        let (tx, rx) = channel();
-        spawn(move || {
-            let mut count = 0;
-
-            loop {
-                sleep(Duration::from_millis(interval_time));
-                subscriber.next(&count);
-
-                count += 1;
-
-                if rx.try_recv().is_ok() {
-                    break;
-                }
-            }
-        });
-
-        Unsubscriber::new(move || {
-            tx.send(()).unwrap();
-        })
-    };
-    Observable::new(Box::new(observer))
-}
\ No newline at end of file
+pub fn interval<S>(period: Duration, scheduler: S) -> Observable<'static, u64>
+    where S: Scheduler + 'static {
+    interval_with_error(period, scheduler)
+}
+
+/// `interval_with_error` is `interval`, for an observable whose error type is `E`.
+pub fn interval_with_error<S, E>(period: Duration, scheduler: S) -> Observable<'static, u64, E>
+    where S: Scheduler + 'static,
+          E: 'static {
+    timer_with_period_with_error(period, period, scheduler)
+}
@@ -0,0 +1,51 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use crate::observer::ObserverLike;
+
+pub struct ReduceObserver<T, U, D, F>
+    where D: ObserverLike<Value=U>,
+          F: FnMut(&U, &T) -> U {
+    destination: D,
+    accumulator: RefCell<F>,
+    state: RefCell<U>,
+    _value: PhantomData<fn(&T)>,
+}
+
+impl<T, U, D, F> ReduceObserver<T, U, D, F>
+    where D: ObserverLike<Value=U>,
+          F: FnMut(&U, &T) -> U {
+    pub fn new(destination: D, seed: U, accumulator: F) -> ReduceObserver<T, U, D, F> {
+        ReduceObserver {
+            destination,
+            accumulator: RefCell::new(accumulator),
+            state: RefCell::new(seed),
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<T, U, D, F> ObserverLike for ReduceObserver<T, U, D, F>
+    where D: ObserverLike<Value=U>,
+          F: FnMut(&U, &T) -> U {
+    type Value = T;
+    type Error = D::Error;
+
+    fn next(&self, value: &Self::Value) {
+        let result = (self.accumulator.borrow_mut())(&self.state.borrow(), value);
+        *self.state.borrow_mut() = result;
+    }
+
+    fn error(&self, e: &Self::Error) {
+        self.destination.error(e);
+    }
+
+    fn complete(&mut self) {
+        self.destination.next(self.state.get_mut());
+        self.destination.complete();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.destination.is_closed()
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::observable::{Observable, ObservableLike};
+use crate::observer::ObserverLike;
+use crate::subscriber::Subscriber;
+use crate::subscription::{Subscription, Unsubscribable};
+
+/// `RepeatNotifier` decides if the source of a repeated `Observable` is subscribed again once
+/// it completes.
+pub enum RepeatNotifier<'a, E> {
+    /// The source is subscribed until it completed the given number of times.
+    Count(u64),
+    /// The source is subscribed again once the observable returned for the number of
+    /// completions emits a value. If it completes without emitting, the repetition completes.
+    When(Box<dyn Fn(u64) -> Observable<'a, (), E> + Send + Sync + 'a>),
+}
+
+/// `RepeatState` is shared by the observers of every subscription to the source (and to the
+/// notifier) of a single subscription to a repeated `Observable`.
+pub struct RepeatState<'a, T, E> {
+    source: Arc<Observable<'a, T, E>>,
+    notifier: Arc<RepeatNotifier<'a, E>>,
+    destination: Mutex<Subscriber<'a, T, E>>,
+    subscription: Subscription,
+    current: Mutex<Option<Subscription>>,
+    completions: AtomicU64,
+    subscribing: AtomicBool,
+    pending: AtomicBool,
+}
+
+impl<'a, T: 'a, E: 'a> RepeatState<'a, T, E> {
+    pub fn new(source: Arc<Observable<'a, T, E>>, notifier: Arc<RepeatNotifier<'a, E>>,
+               destination: Subscriber<'a, T, E>) -> Arc<RepeatState<'a, T, E>> {
+        Arc::new(RepeatState {
+            source,
+            notifier,
+            destination: Mutex::new(destination),
+            subscription: Subscription::empty(),
+            current: Mutex::new(None),
+            completions: AtomicU64::new(0),
+            subscribing: AtomicBool::new(false),
+            pending: AtomicBool::new(false),
+        })
+    }
+
+    /// Returns the `Subscription` grouping the subscriptions to the source and to the notifier.
+    pub fn subscription(&self) -> Subscription {
+        self.subscription.clone()
+    }
+
+    /// Subscribes to the source. A synchronous source completes while it's being subscribed:
+    /// its new subscription is then requested through `pending`, and created once the current
+    /// one returns, instead of growing the stack with every repetition.
+    pub fn subscribe_source(self: &Arc<Self>) {
+        self.pending.store(true, Ordering::SeqCst);
+        if self.subscribing.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        while self.pending.swap(false, Ordering::SeqCst) && !self.subscription.is_closed() {
+            let subscription = self.source.subscribe(RepeatObserver { state: self.clone() });
+            self.replace_current(subscription);
+        }
+        self.subscribing.store(false, Ordering::SeqCst);
+
+        // a completion might have requested a subscription right before the loop finished
+        if self.pending.load(Ordering::SeqCst) {
+            self.subscribe_source();
+        }
+    }
+
+    fn on_complete(self: &Arc<Self>) {
+        let completions = self.completions.fetch_add(1, Ordering::SeqCst) + 1;
+
+        match *self.notifier {
+            RepeatNotifier::Count(count) if completions < count => self.subscribe_source(),
+            RepeatNotifier::Count(_) => self.destination.lock().unwrap().complete(),
+            RepeatNotifier::When(ref notifier) => {
+                let notified = Arc::new(AtomicBool::new(false));
+                let observer = NotifierObserver { state: self.clone(), notified: notified.clone() };
+
+                let mut subscription = notifier(completions).subscribe(observer);
+                if notified.load(Ordering::SeqCst) {
+                    subscription.unsubscribe();
+                } else {
+                    self.replace_current(subscription);
+                }
+            }
+        }
+    }
+
+    /// Replaces the current subscription (to the source or to the notifier) by `subscription`.
+    fn replace_current(&self, subscription: Subscription) {
+        let previous = self.current.lock().unwrap().replace(subscription.clone());
+        if let Some(previous) = previous {
+            self.subscription.remove(&previous);
+        }
+        self.subscription.add(subscription);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.subscription.is_closed() || self.destination.lock().unwrap().is_closed()
+    }
+}
+
+/// `RepeatObserver` observes a single subscription to the source of a repeated `Observable`.
+pub struct RepeatObserver<'a, T, E> {
+    state: Arc<RepeatState<'a, T, E>>,
+}
+
+impl<'a, T: 'a, E: 'a> ObserverLike for RepeatObserver<'a, T, E> {
+    type Value = T;
+    type Error = E;
+
+    fn next(&self, value: &Self::Value) {
+        self.state.destination.lock().unwrap().next(value);
+    }
+
+    fn error(&self, e: &Self::Error) {
+        self.state.destination.lock().unwrap().error(e);
+    }
+
+    fn complete(&mut self) {
+        self.state.on_complete();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.state.is_closed()
+    }
+}
+
+/// `NotifierObserver` observes the notifier returned for a completion of the source, and
+/// subscribes to the source again on its first value.
+struct NotifierObserver<'a, T, E> {
+    state: Arc<RepeatState<'a, T, E>>,
+    notified: Arc<AtomicBool>,
+}
+
+impl<'a, T: 'a, E: 'a> ObserverLike for NotifierObserver<'a, T, E> {
+    type Value = ();
+    type Error = E;
+
+    fn next(&self, _: &Self::Value) {
+        if self.notified.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let current = self.state.current.lock().unwrap().take();
+        if let Some(mut subscription) = current {
+            self.state.subscription.remove(&subscription);
+            subscription.unsubscribe();
+        }
+        self.state.subscribe_source();
+    }
+
+    fn error(&self, e: &Self::Error) {
+        if !self.notified.load(Ordering::SeqCst) {
+            self.state.destination.lock().unwrap().error(e);
+        }
+    }
+
+    fn complete(&mut self) {
+        if !self.notified.load(Ordering::SeqCst) {
+            self.state.destination.lock().unwrap().complete();
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.notified.load(Ordering::SeqCst) || self.state.is_closed()
+    }
+}
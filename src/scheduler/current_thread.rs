@@ -0,0 +1,82 @@
+use std::cell::RefCell;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use crate::scheduler::queue::{TaskQueue, Work};
+use crate::scheduler::{PeriodicTask, ScheduleHandle, Scheduler, Task};
+
+thread_local! {
+    // `None` while no trampoline is running on the current thread
+    static TRAMPOLINE: RefCell<Option<TaskQueue>> = const { RefCell::new(None) };
+}
+
+/// `CurrentThreadScheduler` executes every task on the calling thread, using a trampoline: the
+/// first scheduled task runs synchronously, and the tasks scheduled while it's running are
+/// queued and executed (in due order) once it finishes. Delayed tasks block the calling thread
+/// until they are due.
+#[derive(Clone, Copy, Default)]
+pub struct CurrentThreadScheduler;
+
+impl CurrentThreadScheduler {
+    pub fn new() -> CurrentThreadScheduler {
+        CurrentThreadScheduler
+    }
+
+    /// Returns `true` if a trampoline is running on the current thread.
+    pub fn is_scheduling() -> bool {
+        TRAMPOLINE.with(|queue| queue.borrow().is_some())
+    }
+
+    fn enqueue(&self, due: Instant, work: Work) -> ScheduleHandle {
+        let handle = ScheduleHandle::new();
+        let running = TRAMPOLINE.with(|queue| {
+            let mut queue = queue.borrow_mut();
+            let running = queue.is_some();
+            queue.get_or_insert_with(TaskQueue::default).push(due, handle.clone(), work);
+            running
+        });
+
+        if !running {
+            let _guard = TrampolineGuard;
+            while let Some(item) = TRAMPOLINE.with(|queue| queue.borrow_mut().as_mut().and_then(TaskQueue::pop)) {
+                if !item.is_cancelled() {
+                    sleep(item.due.saturating_duration_since(Instant::now()));
+                }
+                if let Some((due, handle, work)) = item.run() {
+                    TRAMPOLINE.with(|queue| {
+                        queue.borrow_mut().get_or_insert_with(TaskQueue::default).push(due, handle, work)
+                    });
+                }
+            }
+        }
+        handle
+    }
+}
+
+/// `TrampolineGuard` resets the trampoline of the current thread once it finishes, even if one
+/// of its tasks panicked.
+struct TrampolineGuard;
+
+impl Drop for TrampolineGuard {
+    fn drop(&mut self) {
+        TRAMPOLINE.with(|queue| *queue.borrow_mut() = None);
+    }
+}
+
+impl Scheduler for CurrentThreadScheduler {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn schedule(&self, task: Task) -> ScheduleHandle {
+        self.enqueue(Instant::now(), Work::Once(task))
+    }
+
+    fn schedule_after(&self, delay: Duration, task: Task) -> ScheduleHandle {
+        self.enqueue(Instant::now() + delay, Work::Once(task))
+    }
+
+    fn schedule_periodic(&self, initial: Duration, period: Duration, task: PeriodicTask) -> ScheduleHandle {
+        self.enqueue(Instant::now() + initial, Work::Periodic(task, period))
+    }
+}
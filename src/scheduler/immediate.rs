@@ -0,0 +1,45 @@
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use crate::scheduler::{PeriodicTask, ScheduleHandle, Scheduler, Task};
+
+/// `ImmediateScheduler` executes every task synchronously on the calling thread. Delayed tasks
+/// block the calling thread until they are due, and periodic tasks block it until they are
+/// cancelled.
+#[derive(Clone, Copy, Default)]
+pub struct ImmediateScheduler;
+
+impl ImmediateScheduler {
+    pub fn new() -> ImmediateScheduler {
+        ImmediateScheduler
+    }
+}
+
+impl Scheduler for ImmediateScheduler {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn schedule(&self, task: Task) -> ScheduleHandle {
+        task();
+        ScheduleHandle::new()
+    }
+
+    fn schedule_after(&self, delay: Duration, task: Task) -> ScheduleHandle {
+        sleep(delay);
+        task();
+        ScheduleHandle::new()
+    }
+
+    fn schedule_periodic(&self, initial: Duration, period: Duration, mut task: PeriodicTask) -> ScheduleHandle {
+        let handle = ScheduleHandle::new();
+        let mut due = Instant::now() + initial;
+
+        while !handle.is_cancelled() {
+            sleep(due.saturating_duration_since(Instant::now()));
+            task(&handle);
+            due += period;
+        }
+        handle
+    }
+}
@@ -0,0 +1,156 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{current, park_timeout, spawn, JoinHandle};
+use std::time::{Duration, Instant};
+
+pub use crate::scheduler::current_thread::CurrentThreadScheduler;
+pub use crate::scheduler::immediate::ImmediateScheduler;
+pub use crate::scheduler::new_thread::NewThreadScheduler;
+pub use crate::scheduler::thread_pool::ThreadPoolScheduler;
+pub use crate::scheduler::virtual_time::TestScheduler;
+
+mod current_thread;
+mod immediate;
+mod new_thread;
+mod queue;
+mod thread_pool;
+mod virtual_time;
+
+/// `Task` is a unit of work that is executed once by a `Scheduler`.
+pub type Task = Box<dyn FnOnce() + Send>;
+
+/// `PeriodicTask` is a unit of work that is executed periodically by a `Scheduler`. The task
+/// receives its own `ScheduleHandle`, so that it can cancel any further execution.
+pub type PeriodicTask = Box<dyn FnMut(&ScheduleHandle) + Send>;
+
+/// `Scheduler` controls when and where the work of an `Observable` is executed.
+pub trait Scheduler: Send + Sync {
+    /// Returns the current time of the scheduler's clock.
+    fn now(&self) -> Instant;
+
+    /// Schedules a `task` to be executed as soon as possible.
+    fn schedule(&self, task: Task) -> ScheduleHandle;
+
+    /// Schedules a `task` to be executed after a `delay` amount of time.
+    fn schedule_after(&self, delay: Duration, task: Task) -> ScheduleHandle;
+
+    /// Schedules a `task` to be executed after an `initial` delay, and then every `period` of
+    /// time until the returned `ScheduleHandle` is cancelled.
+    fn schedule_periodic(&self, initial: Duration, period: Duration, task: PeriodicTask) -> ScheduleHandle;
+}
+
+/// `ScheduleHandle` is returned by a `Scheduler` for every scheduled task, and can be used to
+/// cancel its execution. The handle of a task executed on its own thread (e.g. by a
+/// `NewThreadScheduler`) also owns the thread, which can be joined or detached.
+#[derive(Clone, Default)]
+pub struct ScheduleHandle {
+    cancelled: Arc<AtomicBool>,
+    worker: Arc<Mutex<Option<Worker>>>,
+}
+
+/// `Worker` is the thread executing a scheduled task.
+struct Worker {
+    thread: JoinHandle<()>,
+    join_on_cancel: bool,
+}
+
+impl ScheduleHandle {
+    pub fn new() -> ScheduleHandle {
+        ScheduleHandle::default()
+    }
+
+    /// Cancels the scheduled task. A task that didn't start yet won't be executed, and a
+    /// periodic task won't be executed again. A thread waiting for the task's next execution
+    /// is woken up, and joined if the scheduler was configured to do so.
+    ///
+    /// The thread isn't joined when the task is cancelled from a worker thread of a scheduler,
+    /// e.g. by an observer unsubscribing on a notification, since the cancelled task might be
+    /// waiting for that notification to be delivered. It can still be joined with `join`.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+
+        let join = {
+            let mut worker = self.worker.lock().unwrap();
+            if let Some(ref worker) = *worker {
+                worker.thread.thread().unpark();
+            }
+            worker.take_if(|worker| worker.join_on_cancel && !is_worker())
+        };
+
+        if let Some(worker) = join {
+            join_worker(worker.thread);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Waits for the thread executing the task to finish. A periodic task finishes once it's
+    /// cancelled. Does nothing if the task isn't executed on its own thread, if the thread was
+    /// already joined or detached, or if it's called from the task itself.
+    pub fn join(&self) {
+        let worker = self.worker.lock().unwrap().take();
+        if let Some(worker) = worker {
+            join_worker(worker.thread);
+        }
+    }
+
+    /// Detaches the thread executing the task, which keeps running on its own: the handle can
+    /// still cancel the task, but not join its thread anymore.
+    pub fn detach(&self) {
+        self.worker.lock().unwrap().take();
+    }
+
+    /// Attaches the `thread` executing the task to the handle.
+    pub(crate) fn attach(&self, thread: JoinHandle<()>, join_on_cancel: bool) {
+        *self.worker.lock().unwrap() = Some(Worker { thread, join_on_cancel });
+
+        // the task might have been cancelled before its thread was attached
+        if self.is_cancelled() {
+            self.cancel();
+        }
+    }
+
+    /// Blocks the current thread until `due`, or until the task is cancelled. Returns `false`
+    /// if the task was cancelled.
+    pub(crate) fn wait_until(&self, due: Instant) -> bool {
+        loop {
+            if self.is_cancelled() {
+                return false;
+            }
+
+            let now = Instant::now();
+            if now >= due {
+                return true;
+            }
+            park_timeout(due - now);
+        }
+    }
+}
+
+thread_local! {
+    static WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Spawns a worker thread executing the tasks of a scheduler.
+pub(crate) fn spawn_worker<F>(work: F) -> JoinHandle<()> where F: FnOnce() + Send + 'static {
+    spawn(move || {
+        WORKER.with(|worker| worker.set(true));
+        work();
+    })
+}
+
+/// Returns `true` if the current thread is a worker thread of a scheduler.
+fn is_worker() -> bool {
+    WORKER.with(Cell::get)
+}
+
+/// Joins a worker thread, unless it's the current thread. A panic of the worker was already
+/// reported by its thread, and isn't propagated to the joining thread.
+fn join_worker(thread: JoinHandle<()>) {
+    if thread.thread().id() != current().id() {
+        let _ = thread.join();
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::{Duration, Instant};
+
+use crate::scheduler::{spawn_worker, PeriodicTask, ScheduleHandle, Scheduler, Task};
+
+/// `NewThreadScheduler` executes every scheduled task on a new thread. The threads wait for the
+/// absolute due time of every execution, so that periodic tasks don't drift by their execution
+/// time, and are woken up as soon as their task is cancelled.
+///
+/// By default the threads are detached. A scheduler created with `join_on_cancel` joins the
+/// thread of a task when it's cancelled, so that the task is guaranteed not to be running
+/// anymore once `cancel` returns. A task cancelled from a worker thread (e.g. when an observer
+/// unsubscribes on a notification) isn't joined, so that the teardown never blocks the
+/// notification. A task must still not be cancelled while holding a lock the task is waiting
+/// for.
+#[derive(Clone, Copy, Default)]
+pub struct NewThreadScheduler {
+    join_on_cancel: bool,
+}
+
+impl NewThreadScheduler {
+    pub fn new() -> NewThreadScheduler {
+        NewThreadScheduler::default()
+    }
+
+    /// Creates a `NewThreadScheduler` whose threads are joined when their task is cancelled.
+    pub fn join_on_cancel() -> NewThreadScheduler {
+        NewThreadScheduler { join_on_cancel: true }
+    }
+}
+
+impl Scheduler for NewThreadScheduler {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn schedule(&self, task: Task) -> ScheduleHandle {
+        self.schedule_after(Duration::from_millis(0), task)
+    }
+
+    fn schedule_after(&self, delay: Duration, task: Task) -> ScheduleHandle {
+        let handle = ScheduleHandle::new();
+        let task_handle = handle.clone();
+        let due = Instant::now() + delay;
+
+        let thread = spawn_worker(move || {
+            if task_handle.wait_until(due) {
+                task();
+            }
+        });
+        handle.attach(thread, self.join_on_cancel);
+        handle
+    }
+
+    fn schedule_periodic(&self, initial: Duration, period: Duration, mut task: PeriodicTask) -> ScheduleHandle {
+        let handle = ScheduleHandle::new();
+        let task_handle = handle.clone();
+        let mut due = Instant::now() + initial;
+
+        let thread = spawn_worker(move || {
+            while task_handle.wait_until(due) {
+                task(&task_handle);
+                due += period;
+            }
+        });
+        handle.attach(thread, self.join_on_cancel);
+        handle
+    }
+}
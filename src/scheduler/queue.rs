@@ -0,0 +1,93 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::time::{Duration, Instant};
+
+use crate::scheduler::{PeriodicTask, ScheduleHandle, Task};
+
+/// `Work` is the scheduled work of a `ScheduledItem`.
+pub(crate) enum Work {
+    Once(Task),
+    Periodic(PeriodicTask, Duration),
+}
+
+/// `ScheduledItem` is a scheduled work, ordered by its due time and its insertion order.
+pub(crate) struct ScheduledItem {
+    pub due: Instant,
+    seq: u64,
+    handle: ScheduleHandle,
+    work: Work,
+}
+
+impl ScheduledItem {
+    pub fn is_cancelled(&self) -> bool {
+        self.handle.is_cancelled()
+    }
+
+    /// Executes the work of the item, if it wasn't cancelled. A periodic work returns the item
+    /// of its next execution, scheduled `period` after the current due time.
+    pub fn run(self) -> Option<(Instant, ScheduleHandle, Work)> {
+        if self.is_cancelled() {
+            return None;
+        }
+
+        match self.work {
+            Work::Once(task) => {
+                task();
+                None
+            }
+            Work::Periodic(mut task, period) => {
+                task(&self.handle);
+                if self.handle.is_cancelled() {
+                    None
+                } else {
+                    Some((self.due + period, self.handle, Work::Periodic(task, period)))
+                }
+            }
+        }
+    }
+}
+
+impl PartialEq for ScheduledItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.due == other.due && self.seq == other.seq
+    }
+}
+
+impl Eq for ScheduledItem {}
+
+impl PartialOrd for ScheduledItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScheduledItem {
+    // reversed, so that the `BinaryHeap` pops the earliest item first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.due.cmp(&self.due).then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// `TaskQueue` is a priority queue of scheduled work, sorted by due time. Work with the same
+/// due time is executed in the order it was scheduled.
+#[derive(Default)]
+pub(crate) struct TaskQueue {
+    items: BinaryHeap<ScheduledItem>,
+    seq: u64,
+}
+
+impl TaskQueue {
+    pub fn push(&mut self, due: Instant, handle: ScheduleHandle, work: Work) {
+        self.seq += 1;
+        self.items.push(ScheduledItem { due, seq: self.seq, handle, work });
+    }
+
+    /// Returns the due time of the earliest item of the queue.
+    pub fn peek_due(&self) -> Option<Instant> {
+        self.items.peek().map(|item| item.due)
+    }
+
+    pub fn pop(&mut self) -> Option<ScheduledItem> {
+        self.items.pop()
+    }
+}
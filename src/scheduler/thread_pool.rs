@@ -0,0 +1,110 @@
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::scheduler::queue::{TaskQueue, Work};
+use crate::scheduler::{spawn_worker, PeriodicTask, ScheduleHandle, Scheduler, Task};
+
+/// `PoolState` is the state shared between a `ThreadPoolScheduler` and its worker threads.
+#[derive(Default)]
+struct PoolState {
+    queue: Mutex<PoolQueue>,
+    available: Condvar,
+}
+
+#[derive(Default)]
+struct PoolQueue {
+    tasks: TaskQueue,
+    shutdown: bool,
+}
+
+impl PoolState {
+    fn push(&self, due: Instant, handle: ScheduleHandle, work: Work) {
+        self.queue.lock().unwrap().tasks.push(due, handle, work);
+        self.available.notify_one();
+    }
+
+    /// Executes the tasks of the queue as they are due, until the pool is shut down and there
+    /// are no pending tasks left.
+    fn work(&self) {
+        let mut queue = self.queue.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            queue = match queue.tasks.peek_due() {
+                None if queue.shutdown => return,
+                None => self.available.wait(queue).unwrap(),
+                Some(due) if due > now => self.available.wait_timeout(queue, due - now).unwrap().0,
+                Some(_) => {
+                    let item = queue.tasks.pop();
+                    drop(queue);
+
+                    if let Some((due, handle, work)) = item.and_then(|item| item.run()) {
+                        self.push(due, handle, work);
+                    }
+                    self.queue.lock().unwrap()
+                }
+            }
+        }
+    }
+}
+
+/// `PoolOwner` shuts down the worker threads once every `ThreadPoolScheduler` handle of the pool
+/// is dropped and the pending tasks are executed.
+struct PoolOwner {
+    state: Arc<PoolState>,
+}
+
+impl Drop for PoolOwner {
+    fn drop(&mut self) {
+        self.state.queue.lock().unwrap().shutdown = true;
+        self.state.available.notify_all();
+    }
+}
+
+/// `ThreadPoolScheduler` executes the scheduled tasks on a fixed number of worker threads.
+/// Cloning the scheduler shares the same pool, and the workers are stopped once every clone is
+/// dropped and no task is pending (a periodic task is pending until it's cancelled).
+#[derive(Clone)]
+pub struct ThreadPoolScheduler {
+    state: Arc<PoolState>,
+    _owner: Arc<PoolOwner>,
+}
+
+impl ThreadPoolScheduler {
+    /// Creates a new `ThreadPoolScheduler` with `size` worker threads.
+    pub fn new(size: usize) -> ThreadPoolScheduler {
+        assert!(size > 0, "a thread pool needs at least one worker");
+
+        let state = Arc::new(PoolState::default());
+        for _ in 0..size {
+            let worker_state = state.clone();
+            spawn_worker(move || worker_state.work());
+        }
+
+        let owner = PoolOwner { state: state.clone() };
+        ThreadPoolScheduler { state, _owner: Arc::new(owner) }
+    }
+
+    fn enqueue(&self, due: Instant, work: Work) -> ScheduleHandle {
+        let handle = ScheduleHandle::new();
+        self.state.push(due, handle.clone(), work);
+        handle
+    }
+}
+
+impl Scheduler for ThreadPoolScheduler {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn schedule(&self, task: Task) -> ScheduleHandle {
+        self.enqueue(Instant::now(), Work::Once(task))
+    }
+
+    fn schedule_after(&self, delay: Duration, task: Task) -> ScheduleHandle {
+        self.enqueue(Instant::now() + delay, Work::Once(task))
+    }
+
+    fn schedule_periodic(&self, initial: Duration, period: Duration, task: PeriodicTask) -> ScheduleHandle {
+        self.enqueue(Instant::now() + initial, Work::Periodic(task, period))
+    }
+}
@@ -0,0 +1,138 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::scheduler::queue::{TaskQueue, Work};
+use crate::scheduler::{PeriodicTask, ScheduleHandle, Scheduler, Task};
+
+struct VirtualClock {
+    start: Instant,
+    elapsed: Duration,
+    tasks: TaskQueue,
+}
+
+/// `TestScheduler` is a `Scheduler` running on a virtual clock, which only moves forward when
+/// it's explicitly advanced. The scheduled tasks are executed (in due order) while advancing the
+/// clock, which makes time-based operators testable deterministically and without waiting.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rxrs::operators::interval;
+/// use rxrs::scheduler::TestScheduler;
+///
+/// let scheduler = TestScheduler::new();
+/// interval(Duration::from_millis(10), scheduler.clone()).subscribe_next(|value| println!("{}", value));
+///
+/// // emits 0, 1 and 2 without any real waiting
+/// scheduler.advance_by(Duration::from_millis(30));
+/// ```
+#[derive(Clone)]
+pub struct TestScheduler {
+    clock: Arc<Mutex<VirtualClock>>,
+}
+
+impl Default for TestScheduler {
+    fn default() -> TestScheduler {
+        TestScheduler::new()
+    }
+}
+
+impl TestScheduler {
+    pub fn new() -> TestScheduler {
+        let clock = VirtualClock { start: Instant::now(), elapsed: Duration::from_millis(0), tasks: TaskQueue::default() };
+        TestScheduler { clock: Arc::new(Mutex::new(clock)) }
+    }
+
+    /// Returns the virtual time elapsed since the creation of the scheduler.
+    pub fn elapsed(&self) -> Duration {
+        self.clock.lock().unwrap().elapsed
+    }
+
+    /// Advances the virtual clock by `time`, executing every task that is due until then.
+    pub fn advance_by(&self, time: Duration) {
+        let target = self.elapsed() + time;
+        self.advance_to(target);
+    }
+
+    /// Advances the virtual clock to the absolute virtual `time` (since the creation of the
+    /// scheduler), executing every task that is due until then. The clock never goes backwards.
+    pub fn advance_to(&self, time: Duration) {
+        let target = self.clock.lock().unwrap().start + time;
+        while self.run_next(|due| due <= target) {}
+        self.set_elapsed(time);
+    }
+
+    /// Advances the virtual clock to the absolute virtual `time`, executing only the tasks that
+    /// are due strictly before it. The tasks due at `time` stay pending.
+    pub(crate) fn advance_before(&self, time: Duration) {
+        let target = self.clock.lock().unwrap().start + time;
+        while self.run_next(|due| due < target) {}
+        self.set_elapsed(time);
+    }
+
+    /// Executes every pending task, advancing the virtual clock to the due time of the last one.
+    /// Periodic tasks that are never cancelled make `flush` run forever.
+    pub fn flush(&self) {
+        while self.run_next(|_| true) {}
+    }
+
+    fn set_elapsed(&self, time: Duration) {
+        let mut clock = self.clock.lock().unwrap();
+        clock.elapsed = clock.elapsed.max(time);
+    }
+
+    /// Executes the next pending task if it's due, moving the clock to its due time. Returns
+    /// `false` if there was no task to execute.
+    fn run_next<P>(&self, is_due: P) -> bool where P: Fn(Instant) -> bool {
+        let item = {
+            let mut clock = self.clock.lock().unwrap();
+            match clock.tasks.peek_due() {
+                Some(due) if is_due(due) => {
+                    let item = clock.tasks.pop();
+                    if item.as_ref().is_some_and(|item| !item.is_cancelled()) {
+                        clock.elapsed = clock.elapsed.max(due - clock.start);
+                    }
+                    item
+                }
+                _ => None
+            }
+        };
+
+        match item {
+            Some(item) => {
+                if let Some((due, handle, work)) = item.run() {
+                    self.clock.lock().unwrap().tasks.push(due, handle, work);
+                }
+                true
+            }
+            None => false
+        }
+    }
+
+    fn enqueue(&self, delay: Duration, work: Work) -> ScheduleHandle {
+        let handle = ScheduleHandle::new();
+        let mut clock = self.clock.lock().unwrap();
+
+        let due = clock.start + clock.elapsed + delay;
+        clock.tasks.push(due, handle.clone(), work);
+        handle
+    }
+}
+
+impl Scheduler for TestScheduler {
+    fn now(&self) -> Instant {
+        let clock = self.clock.lock().unwrap();
+        clock.start + clock.elapsed
+    }
+
+    fn schedule(&self, task: Task) -> ScheduleHandle {
+        self.enqueue(Duration::from_millis(0), Work::Once(task))
+    }
+
+    fn schedule_after(&self, delay: Duration, task: Task) -> ScheduleHandle {
+        self.enqueue(delay, Work::Once(task))
+    }
+
+    fn schedule_periodic(&self, initial: Duration, period: Duration, task: PeriodicTask) -> ScheduleHandle {
+        self.enqueue(initial, Work::Periodic(task, period))
+    }
+}
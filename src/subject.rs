@@ -1,49 +1,226 @@
-use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use crate::error::RxError;
 use crate::observable::ObservableLike;
 use crate::observer::{Observer, ObserverLike};
-use crate::subscription::{SubjectSubscription, TrackedSubjectObservers, Unsubscribable};
+use crate::scheduler::Scheduler;
+use crate::subscriber::Subscriber;
+use crate::subscription::{ObserverKey, SubjectObserver, SubjectObservers, SubjectSubscription, TrackedSubjectObservers, Unsubscribable};
 
-#[derive(Default)]
-pub struct Subject<T, O> where O: ObserverLike<Value=T, Error=RxError> {
-    pub closed: bool,
-    observers: TrackedSubjectObservers<O>,
+/// `Subject` is an `Observable` that multicasts the events it receives (as an `ObserverLike`) to
+/// all its observers. A `Subject` with an error type other than `RxError` can be created with
+/// `Subject::default()`.
+///
+/// A `Subject` is a handle over a shared list of observers: it is `Send + Sync`, and its clones
+/// push into and subscribe to the same subject, so that it can be driven from several threads.
+///
+/// Once a `Subject` receives an error or completes, it is stopped: further events are ignored,
+/// and late observers receive the terminal event as soon as they subscribe.
+///
+/// Events are emitted one at a time. An event received while the subject is emitting (e.g. an
+/// observer calling `next` on the subject, or another thread pushing a value) is queued, and
+/// emitted in order once the current event has been delivered to every observer. Observers can
+/// subscribe or unsubscribe at any time, including from their own handlers.
+pub struct Subject<T, E = RxError> {
+    closed: Arc<AtomicBool>,
+    observers: TrackedSubjectObservers<T, E>,
+    emission: Arc<Mutex<Emission<T, E>>>,
 }
 
-impl<'a, T, O> Subject<T, O> where O: ObserverLike<Value=T, Error=RxError> {
-    pub fn new() -> Subject<T, O> {
-        Subject { closed: false, observers: RefCell::new(Vec::new()) }
+/// Event queued for emission by a `Subject`.
+enum Event<T, E> {
+    Next(T),
+    Error(E),
+    Complete,
+    /// Replays the values (and the terminal event) to a pending observer, and activates it.
+    Replay(ObserverKey, Arc<SubjectObserver<T, E>>, Vec<T>, Option<Termination<E>>),
+}
+
+struct Emission<T, E> {
+    emitting: bool,
+    queue: VecDeque<Event<T, E>>,
+    termination: Option<Termination<E>>,
+}
+
+impl<T> Subject<T> {
+    pub fn new() -> Subject<T> {
+        Subject::default()
+    }
+}
+
+impl<T, E> Default for Subject<T, E> {
+    fn default() -> Subject<T, E> {
+        Subject {
+            closed: Arc::new(AtomicBool::new(false)),
+            observers: Arc::new(Mutex::new(SubjectObservers::new())),
+            emission: Arc::new(Mutex::new(Emission { emitting: false, queue: VecDeque::new(), termination: None })),
+        }
+    }
+}
+
+impl<T, E> Clone for Subject<T, E> {
+    fn clone(&self) -> Subject<T, E> {
+        Subject { closed: self.closed.clone(), observers: self.observers.clone(), emission: self.emission.clone() }
     }
 }
 
-impl<'a, T> Subject<T, Observer<T>> {
-    pub fn subscribe_next<N>(&'a self, next: N) -> SubjectSubscription<'a, Observer<T>>
+impl<T, E> Subject<T, E> {
+    /// Returns `true` once the subject has been unsubscribed.
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of observers subscribed to the subject.
+    pub fn observer_count(&self) -> usize {
+        self.observers.lock().unwrap().len()
+    }
+
+    /// Returns `true` once the subject received an error or completed.
+    pub fn is_stopped(&self) -> bool {
+        self.emission.lock().unwrap().termination.is_some()
+    }
+
+    /// Returns `true` if the subject stopped with an error.
+    pub fn has_error(&self) -> bool {
+        matches!(self.emission.lock().unwrap().termination, Some(Termination::Error(_)))
+    }
+
+    /// Queues an `event` for emission, unless the subject is stopped. A terminal event stops the
+    /// subject. Returns whether the caller has to `drain` the queue (i.e. if the subject wasn't
+    /// emitting already), or `None` if the event was ignored.
+    fn push(&self, event: Event<T, E>) -> Option<bool> where E: Clone {
+        let mut emission = self.emission.lock().unwrap();
+        if emission.termination.is_some() {
+            return None;
+        }
+
+        match event {
+            Event::Error(ref e) => emission.termination = Some(Termination::Error(e.clone())),
+            Event::Complete => emission.termination = Some(Termination::Complete),
+            _ => {}
+        }
+        emission.queue.push_back(event);
+
+        Some(!std::mem::replace(&mut emission.emitting, true))
+    }
+
+    /// Registers a pending `observer`, and queues the replay of the `values` (computed from the
+    /// terminal state of the subject) and of the terminal event, if any. Returns whether the
+    /// caller has to `drain` the queue, and the subscription of the observer. An observer of a
+    /// closed subject isn't registered, and gets a closed subscription.
+    fn push_replay<F>(&self, observer: Subscriber<'static, T, E>, values: F) -> (bool, SubjectSubscription<T, E>)
+        where E: Clone,
+              F: FnOnce(Option<&Termination<E>>) -> Vec<T> {
+        if self.is_closed() {
+            return (false, SubjectSubscription::closed(&self.observers));
+        }
+
+        let observer = Arc::new(SubjectObserver::pending(observer));
+        let key = self.observers.lock().unwrap().insert(observer.clone());
+
+        let mut emission = self.emission.lock().unwrap();
+        let values = values(emission.termination.as_ref());
+        let termination = emission.termination.clone();
+        emission.queue.push_back(Event::Replay(key, observer, values, termination));
+        let subscription = SubjectSubscription::new(&self.observers, key);
+
+        (!std::mem::replace(&mut emission.emitting, true), subscription)
+    }
+
+    /// Emits the queued events until the queue is empty, including the events queued meanwhile.
+    fn drain(&self) {
+        loop {
+            let event = {
+                let mut emission = self.emission.lock().unwrap();
+                match emission.queue.pop_front() {
+                    Some(event) => event,
+                    None => {
+                        emission.emitting = false;
+                        return;
+                    }
+                }
+            };
+
+            match event {
+                Event::Next(value) => self.notify(|observer| observer.next(&value)),
+                Event::Error(e) => {
+                    self.notify(|observer| observer.error(&e));
+                    self.observers.lock().unwrap().clear();
+                }
+                Event::Complete => {
+                    self.notify(|observer| observer.complete());
+                    self.observers.lock().unwrap().clear();
+                }
+                Event::Replay(key, observer, values, termination) => {
+                    if !observer.is_closed() {
+                        let mut destination = observer.observer();
+                        values.iter().for_each(|value| destination.next(value));
+                        if let Some(ref termination) = termination {
+                            termination.notify(&mut destination);
+                        }
+                    }
+
+                    // a stopped subject doesn't keep its observers
+                    match termination {
+                        Some(_) => drop(self.observers.lock().unwrap().remove(key)),
+                        None => observer.activate()
+                    }
+                }
+            }
+        }
+    }
+
+    fn emit(&self, event: Event<T, E>) where E: Clone {
+        if self.push(event) == Some(true) {
+            self.drain();
+        }
+    }
+
+    /// Notifies every active observer. The list is copied upfront and not locked while notifying,
+    /// so that the observers can subscribe and unsubscribe from their handlers.
+    fn notify<F>(&self, mut notification: F) where F: FnMut(&mut Subscriber<'static, T, E>) {
+        let observers: Vec<Arc<SubjectObserver<T, E>>> = self.observers.lock().unwrap().iter()
+            .filter(|observer| observer.is_active())
+            .cloned()
+            .collect();
+
+        observers.iter()
+            // an observer could be unsubscribed by the handler of a previous one
+            .filter(|observer| observer.is_active())
+            .for_each(|observer| notification(&mut observer.observer()));
+    }
+}
+
+impl<T: 'static, E: Clone + 'static> Subject<T, E> {
+    pub fn subscribe_next<N>(&self, next: N) -> SubjectSubscription<T, E>
         where N: Fn(&T) + 'static + Send {
         self.subscribe_all(next, |_| {}, || {})
     }
 
-    pub fn subscribe_error<E>(&'a self, error: E) -> SubjectSubscription<'a, Observer<T>>
-        where E: Fn(&RxError) + 'static + Send {
+    pub fn subscribe_error<EF>(&self, error: EF) -> SubjectSubscription<T, E>
+        where EF: Fn(&E) + 'static + Send {
         self.subscribe_all(|_| {}, error, || {})
     }
 
-    pub fn subscribe_complete<C>(&'a self, complete: C) -> SubjectSubscription<'a, Observer<T>>
+    pub fn subscribe_complete<C>(&self, complete: C) -> SubjectSubscription<T, E>
         where C: Fn() + 'static + Send {
         self.subscribe_all(|_| {}, |_| {}, complete)
     }
 
-    pub fn subscribe_all<F, E, C>(
-        &'a self,
+    pub fn subscribe_all<F, EF, C>(
+        &self,
         next_handler: F,
-        error_handler: E,
+        error_handler: EF,
         complete_handler: C,
-    ) -> SubjectSubscription<'a, Observer<T>>
+    ) -> SubjectSubscription<T, E>
         where F: Fn(&T) + 'static + Send,
-              E: Fn(&RxError) + 'static + Send,
+              EF: Fn(&E) + 'static + Send,
               C: Fn() + 'static + Send {
         // generate a subscriber from the input events
-        let observer = Observer::<T>::new(
+        let observer = Observer::<T, E>::new(
             next_handler, error_handler, complete_handler,
         );
 
@@ -51,60 +228,530 @@ impl<'a, T> Subject<T, Observer<T>> {
     }
 }
 
-impl<'a, T, O: 'a> ObservableLike<'a> for Subject<T, O>
-    where O: ObserverLike<Value=T, Error=RxError> {
-    type Observer = O;
-    type Subscription = SubjectSubscription<'a, O>;
+impl<T, E: Clone> ObservableLike<'static> for Subject<T, E> {
+    type Value = T;
+    type Error = E;
+    type Subscription = SubjectSubscription<T, E>;
+
+    fn subscribe<O>(&self, observer: O) -> SubjectSubscription<T, E>
+        where O: ObserverLike<Value=T, Error=E> + Send + 'static {
+        let (drain, subscription) = self.push_replay(Subscriber::new(observer), |_| Vec::new());
 
-    fn subscribe(&'a self, observer: O) -> SubjectSubscription<'a, O> {
-        self.observers.borrow_mut().push(Some(observer));
-        SubjectSubscription::new(&self.observers)
+        if drain {
+            self.drain();
+        }
+        subscription
     }
 }
 
-impl<T, O> ObserverLike for Subject<T, O> where O: ObserverLike<Value=T, Error=RxError> {
+impl<T: Clone, E: Clone> ObserverLike for Subject<T, E> {
     type Value = T;
-    type Error = RxError;
+    type Error = E;
 
     fn next(&self, value: &Self::Value) {
-        if !self.closed {
-            self.observers.borrow().iter()
-                .for_each(|item| {
-                    if let Some(observer) = item {
-                        observer.next(value);
-                    }
-                });
+        if !self.is_closed() {
+            self.emit(Event::Next(value.clone()));
         }
     }
 
     fn error(&self, e: &Self::Error) {
-        if !self.closed {
-            self.observers.borrow().iter()
-                .for_each(|item| {
-                    if let Some(observer) = item {
-                        observer.error(e);
-                    }
-                });
+        if !self.is_closed() {
+            self.emit(Event::Error(e.clone()));
         }
     }
 
     fn complete(&mut self) {
-        if !self.closed {
-            self.observers.borrow_mut().iter_mut()
-                .for_each(|item| {
-                    if let Some(observer) = item {
-                        observer.complete();
-                    }
-                });
+        if !self.is_closed() {
+            self.emit(Event::Complete);
         }
     }
+
+    fn is_closed(&self) -> bool {
+        Subject::is_closed(self) || self.is_stopped()
+    }
 }
 
-impl<T, O> Unsubscribable for Subject<T, O> where O: ObserverLike<Value=T, Error=RxError> {
+impl<T, E> Unsubscribable for Subject<T, E> {
     fn unsubscribe(&mut self) {
-        if !self.closed {
-            self.closed = true;
-            self.observers = RefCell::new(Vec::new())
+        if !self.closed.swap(true, Ordering::SeqCst) {
+            self.observers.lock().unwrap().clear();
+        }
+    }
+}
+
+/// Terminal event received by a subject, replayed to its late subscribers.
+#[derive(Clone)]
+enum Termination<E> {
+    Error(E),
+    Complete,
+}
+
+impl<E> Termination<E> {
+    fn notify<T>(&self, observer: &mut Subscriber<'static, T, E>) {
+        match self {
+            Termination::Error(e) => observer.error(e),
+            Termination::Complete => observer.complete(),
+        }
+    }
+}
+
+/// `BehaviorSubject` is a `Subject` holding a current value. Every new observer receives the
+/// current value on subscription, or the terminal event if the subject already errored or
+/// completed. A `BehaviorSubject` with an error type other than `RxError` can be created with
+/// `BehaviorSubject::with_value`.
+pub struct BehaviorSubject<T, E = RxError> {
+    value: Arc<Mutex<T>>,
+    subject: Subject<T, E>,
+}
+
+impl<T> BehaviorSubject<T> {
+    pub fn new(value: T) -> BehaviorSubject<T> {
+        BehaviorSubject::with_value(value)
+    }
+}
+
+impl<T, E> BehaviorSubject<T, E> {
+    pub fn with_value(value: T) -> BehaviorSubject<T, E> {
+        BehaviorSubject {
+            value: Arc::new(Mutex::new(value)),
+            subject: Subject::default(),
         }
     }
 }
+
+impl<T, E> Clone for BehaviorSubject<T, E> {
+    fn clone(&self) -> BehaviorSubject<T, E> {
+        BehaviorSubject { value: self.value.clone(), subject: self.subject.clone() }
+    }
+}
+
+impl<T: Clone, E: Clone> BehaviorSubject<T, E> {
+    /// Returns the current value, even if the subject already errored.
+    pub fn value(&self) -> T {
+        self.value.lock().unwrap().clone()
+    }
+
+    /// Returns the current value, or the error received by the subject.
+    pub fn get_value(&self) -> Result<T, E> {
+        let value = self.value.lock().unwrap();
+        match self.subject.emission.lock().unwrap().termination {
+            Some(Termination::Error(ref e)) => Err(e.clone()),
+            _ => Ok(value.clone())
+        }
+    }
+}
+
+impl<T: Clone + 'static, E: Clone + 'static> BehaviorSubject<T, E> {
+    pub fn subscribe_next<N>(&self, next: N) -> SubjectSubscription<T, E>
+        where N: Fn(&T) + 'static + Send {
+        self.subscribe_all(next, |_| {}, || {})
+    }
+
+    pub fn subscribe_error<EF>(&self, error: EF) -> SubjectSubscription<T, E>
+        where EF: Fn(&E) + 'static + Send {
+        self.subscribe_all(|_| {}, error, || {})
+    }
+
+    pub fn subscribe_complete<C>(&self, complete: C) -> SubjectSubscription<T, E>
+        where C: Fn() + 'static + Send {
+        self.subscribe_all(|_| {}, |_| {}, complete)
+    }
+
+    pub fn subscribe_all<F, EF, C>(
+        &self,
+        next_handler: F,
+        error_handler: EF,
+        complete_handler: C,
+    ) -> SubjectSubscription<T, E>
+        where F: Fn(&T) + 'static + Send,
+              EF: Fn(&E) + 'static + Send,
+              C: Fn() + 'static + Send {
+        let observer = Observer::<T, E>::new(
+            next_handler, error_handler, complete_handler,
+        );
+
+        self.subscribe(observer)
+    }
+}
+
+impl<T: Clone + 'static, E: Clone + 'static> ObservableLike<'static> for BehaviorSubject<T, E> {
+    type Value = T;
+    type Error = E;
+    type Subscription = SubjectSubscription<T, E>;
+
+    fn subscribe<O>(&self, observer: O) -> SubjectSubscription<T, E>
+        where O: ObserverLike<Value=T, Error=E> + Send + 'static {
+        let (drain, subscription) = {
+            // the replay is queued with the value locked, so that no value is missed or repeated
+            let value = self.value.lock().unwrap();
+            self.subject.push_replay(Subscriber::new(observer), |termination| match termination {
+                Some(_) => Vec::new(),
+                None => vec![value.clone()]
+            })
+        };
+
+        if drain {
+            self.subject.drain();
+        }
+        subscription
+    }
+}
+
+impl<T: Clone, E: Clone> ObserverLike for BehaviorSubject<T, E> {
+    type Value = T;
+    type Error = E;
+
+    fn next(&self, value: &Self::Value) {
+        if self.subject.is_closed() {
+            return;
+        }
+
+        let drain = {
+            let mut current = self.value.lock().unwrap();
+            match self.subject.push(Event::Next(value.clone())) {
+                Some(drain) => {
+                    *current = value.clone();
+                    drain
+                }
+                None => return
+            }
+        };
+
+        if drain {
+            self.subject.drain();
+        }
+    }
+
+    fn error(&self, e: &Self::Error) {
+        self.subject.error(e);
+    }
+
+    fn complete(&mut self) {
+        self.subject.complete();
+    }
+
+    fn is_closed(&self) -> bool {
+        ObserverLike::is_closed(&self.subject)
+    }
+}
+
+impl<T, E> Unsubscribable for BehaviorSubject<T, E> {
+    fn unsubscribe(&mut self) {
+        self.subject.unsubscribe();
+    }
+}
+
+/// Values buffered by a `ReplaySubject`, with their time of arrival if the buffer has a window.
+type ReplayBuffer<T> = VecDeque<(Option<Instant>, T)>;
+
+/// `ReplaySubject` is a `Subject` buffering the values it receives, and replaying them to every
+/// new observer before any live value, followed by the terminal event if the subject already
+/// errored or completed. The buffer can be limited to the last `buffer_size` values, and to the
+/// values received within a `window` of time measured with the clock of a `Scheduler`.
+pub struct ReplaySubject<T, E = RxError> {
+    buffer_size: Option<usize>,
+    window: Option<(Duration, Arc<dyn Scheduler>)>,
+    buffer: Arc<Mutex<ReplayBuffer<T>>>,
+    subject: Subject<T, E>,
+}
+
+impl<T> ReplaySubject<T> {
+    /// Creates a `ReplaySubject` with an unbounded buffer.
+    pub fn new() -> ReplaySubject<T> {
+        ReplaySubject::default()
+    }
+}
+
+impl<T, E> Default for ReplaySubject<T, E> {
+    fn default() -> ReplaySubject<T, E> {
+        ReplaySubject::with_limits(None, None)
+    }
+}
+
+impl<T, E> Clone for ReplaySubject<T, E> {
+    fn clone(&self) -> ReplaySubject<T, E> {
+        ReplaySubject {
+            buffer_size: self.buffer_size,
+            window: self.window.clone(),
+            buffer: self.buffer.clone(),
+            subject: self.subject.clone(),
+        }
+    }
+}
+
+impl<T, E> ReplaySubject<T, E> {
+    /// Creates a `ReplaySubject` replaying the last `buffer_size` values.
+    pub fn with_buffer_size(buffer_size: usize) -> ReplaySubject<T, E> {
+        ReplaySubject::with_limits(Some(buffer_size), None)
+    }
+
+    /// Creates a `ReplaySubject` replaying the values received within the last `window` of time,
+    /// according to the clock of the `scheduler`.
+    pub fn with_window<S>(window: Duration, scheduler: S) -> ReplaySubject<T, E>
+        where S: Scheduler + 'static {
+        ReplaySubject::with_limits(None, Some((window, Arc::new(scheduler))))
+    }
+
+    /// Creates a `ReplaySubject` replaying at most the last `buffer_size` values received within
+    /// the last `window` of time, according to the clock of the `scheduler`.
+    pub fn with_buffer_size_and_window<S>(buffer_size: usize, window: Duration, scheduler: S) -> ReplaySubject<T, E>
+        where S: Scheduler + 'static {
+        ReplaySubject::with_limits(Some(buffer_size), Some((window, Arc::new(scheduler))))
+    }
+
+    fn with_limits(buffer_size: Option<usize>, window: Option<(Duration, Arc<dyn Scheduler>)>) -> ReplaySubject<T, E> {
+        ReplaySubject {
+            buffer_size,
+            window,
+            buffer: Arc::new(Mutex::new(VecDeque::new())),
+            subject: Subject::default(),
+        }
+    }
+
+    /// Drops the buffered values exceeding the buffer size or outside of the time window.
+    fn trim(&self, buffer: &mut ReplayBuffer<T>) {
+        if let Some(buffer_size) = self.buffer_size {
+            while buffer.len() > buffer_size {
+                buffer.pop_front();
+            }
+        }
+
+        if let Some((window, ref scheduler)) = self.window {
+            let now = scheduler.now();
+            while buffer.front().is_some_and(|(time, _)| time.is_some_and(|time| now.duration_since(time) >= window)) {
+                buffer.pop_front();
+            }
+        }
+    }
+}
+
+impl<T: Clone + 'static, E: Clone + 'static> ReplaySubject<T, E> {
+    pub fn subscribe_next<N>(&self, next: N) -> SubjectSubscription<T, E>
+        where N: Fn(&T) + 'static + Send {
+        self.subscribe_all(next, |_| {}, || {})
+    }
+
+    pub fn subscribe_error<EF>(&self, error: EF) -> SubjectSubscription<T, E>
+        where EF: Fn(&E) + 'static + Send {
+        self.subscribe_all(|_| {}, error, || {})
+    }
+
+    pub fn subscribe_complete<C>(&self, complete: C) -> SubjectSubscription<T, E>
+        where C: Fn() + 'static + Send {
+        self.subscribe_all(|_| {}, |_| {}, complete)
+    }
+
+    pub fn subscribe_all<F, EF, C>(
+        &self,
+        next_handler: F,
+        error_handler: EF,
+        complete_handler: C,
+    ) -> SubjectSubscription<T, E>
+        where F: Fn(&T) + 'static + Send,
+              EF: Fn(&E) + 'static + Send,
+              C: Fn() + 'static + Send {
+        let observer = Observer::<T, E>::new(
+            next_handler, error_handler, complete_handler,
+        );
+
+        self.subscribe(observer)
+    }
+}
+
+impl<T: Clone + 'static, E: Clone + 'static> ObservableLike<'static> for ReplaySubject<T, E> {
+    type Value = T;
+    type Error = E;
+    type Subscription = SubjectSubscription<T, E>;
+
+    fn subscribe<O>(&self, observer: O) -> SubjectSubscription<T, E>
+        where O: ObserverLike<Value=T, Error=E> + Send + 'static {
+        let (drain, subscription) = {
+            let mut buffer = self.buffer.lock().unwrap();
+            self.trim(&mut buffer);
+
+            self.subject.push_replay(Subscriber::new(observer), |_| {
+                buffer.iter().map(|(_, value)| value.clone()).collect()
+            })
+        };
+
+        if drain {
+            self.subject.drain();
+        }
+        subscription
+    }
+}
+
+impl<T: Clone, E: Clone> ObserverLike for ReplaySubject<T, E> {
+    type Value = T;
+    type Error = E;
+
+    fn next(&self, value: &Self::Value) {
+        if self.subject.is_closed() {
+            return;
+        }
+
+        let drain = {
+            let mut buffer = self.buffer.lock().unwrap();
+            let drain = match self.subject.push(Event::Next(value.clone())) {
+                Some(drain) => drain,
+                None => return
+            };
+
+            let time = self.window.as_ref().map(|(_, scheduler)| scheduler.now());
+            buffer.push_back((time, value.clone()));
+            self.trim(&mut buffer);
+            drain
+        };
+
+        if drain {
+            self.subject.drain();
+        }
+    }
+
+    fn error(&self, e: &Self::Error) {
+        self.subject.error(e);
+    }
+
+    fn complete(&mut self) {
+        self.subject.complete();
+    }
+
+    fn is_closed(&self) -> bool {
+        ObserverLike::is_closed(&self.subject)
+    }
+}
+
+impl<T, E> Unsubscribable for ReplaySubject<T, E> {
+    fn unsubscribe(&mut self) {
+        self.subject.unsubscribe();
+    }
+}
+
+/// `AsyncSubject` is a `Subject` emitting only the last value it received, followed by the
+/// completion, once it completes. Observers subscribing after the completion receive the same
+/// events. If the subject errors, the error is forwarded without any value.
+pub struct AsyncSubject<T, E = RxError> {
+    last: Arc<Mutex<Option<T>>>,
+    subject: Subject<T, E>,
+}
+
+impl<T> AsyncSubject<T> {
+    pub fn new() -> AsyncSubject<T> {
+        AsyncSubject::default()
+    }
+}
+
+impl<T, E> Default for AsyncSubject<T, E> {
+    fn default() -> AsyncSubject<T, E> {
+        AsyncSubject {
+            last: Arc::new(Mutex::new(None)),
+            subject: Subject::default(),
+        }
+    }
+}
+
+impl<T, E> Clone for AsyncSubject<T, E> {
+    fn clone(&self) -> AsyncSubject<T, E> {
+        AsyncSubject { last: self.last.clone(), subject: self.subject.clone() }
+    }
+}
+
+impl<T: Clone + 'static, E: Clone + 'static> AsyncSubject<T, E> {
+    pub fn subscribe_next<N>(&self, next: N) -> SubjectSubscription<T, E>
+        where N: Fn(&T) + 'static + Send {
+        self.subscribe_all(next, |_| {}, || {})
+    }
+
+    pub fn subscribe_error<EF>(&self, error: EF) -> SubjectSubscription<T, E>
+        where EF: Fn(&E) + 'static + Send {
+        self.subscribe_all(|_| {}, error, || {})
+    }
+
+    pub fn subscribe_complete<C>(&self, complete: C) -> SubjectSubscription<T, E>
+        where C: Fn() + 'static + Send {
+        self.subscribe_all(|_| {}, |_| {}, complete)
+    }
+
+    pub fn subscribe_all<F, EF, C>(
+        &self,
+        next_handler: F,
+        error_handler: EF,
+        complete_handler: C,
+    ) -> SubjectSubscription<T, E>
+        where F: Fn(&T) + 'static + Send,
+              EF: Fn(&E) + 'static + Send,
+              C: Fn() + 'static + Send {
+        let observer = Observer::<T, E>::new(
+            next_handler, error_handler, complete_handler,
+        );
+
+        self.subscribe(observer)
+    }
+}
+
+impl<T: Clone + 'static, E: Clone + 'static> ObservableLike<'static> for AsyncSubject<T, E> {
+    type Value = T;
+    type Error = E;
+    type Subscription = SubjectSubscription<T, E>;
+
+    fn subscribe<O>(&self, observer: O) -> SubjectSubscription<T, E>
+        where O: ObserverLike<Value=T, Error=E> + Send + 'static {
+        let (drain, subscription) = {
+            let last = self.last.lock().unwrap();
+            self.subject.push_replay(Subscriber::new(observer), |termination| match (termination, &*last) {
+                (Some(Termination::Complete), Some(last)) => vec![last.clone()],
+                _ => Vec::new()
+            })
+        };
+
+        if drain {
+            self.subject.drain();
+        }
+        subscription
+    }
+}
+
+impl<T: Clone, E: Clone> ObserverLike for AsyncSubject<T, E> {
+    type Value = T;
+    type Error = E;
+
+    fn next(&self, value: &Self::Value) {
+        let mut last = self.last.lock().unwrap();
+        if !ObserverLike::is_closed(&self.subject) {
+            *last = Some(value.clone());
+        }
+    }
+
+    fn error(&self, e: &Self::Error) {
+        self.subject.error(e);
+    }
+
+    fn complete(&mut self) {
+        let drain = {
+            let last = self.last.lock().unwrap();
+            let drain = match *last {
+                Some(ref last) => self.subject.push(Event::Next(last.clone())),
+                None => Some(false)
+            };
+
+            match drain {
+                Some(drain) => self.subject.push(Event::Complete) == Some(true) || drain,
+                None => return
+            }
+        };
+
+        if drain {
+            self.subject.drain();
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        ObserverLike::is_closed(&self.subject)
+    }
+}
+
+impl<T, E> Unsubscribable for AsyncSubject<T, E> {
+    fn unsubscribe(&mut self) {
+        self.subject.unsubscribe();
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::RxError;
+use crate::observer::ObserverLike;
+use crate::subscription::{Subscription, Unsubscribable};
+
+/// `Subscriber` is the type-erased `ObserverLike` handed to the constructor of an `Observable`.
+/// It can wrap any observer (an `Observer`, a `Subject` or a custom `ObserverLike`
+/// implementation), so that an `Observable` doesn't depend on the type of its final observer.
+pub struct Subscriber<'a, T, E = RxError> {
+    destination: Box<dyn ObserverLike<Value=T, Error=E> + Send + 'a>,
+}
+
+impl<'a, T, E> Subscriber<'a, T, E> {
+    /// Creates a new `Subscriber` wrapping the `observer` input parameter.
+    pub fn new<O>(observer: O) -> Subscriber<'a, T, E>
+        where O: ObserverLike<Value=T, Error=E> + Send + 'a {
+        Subscriber { destination: Box::new(observer) }
+    }
+}
+
+impl<'a, T, E> ObserverLike for Subscriber<'a, T, E> {
+    type Value = T;
+    type Error = E;
+
+    fn next(&self, value: &Self::Value) {
+        self.destination.next(value);
+    }
+
+    fn error(&self, e: &Self::Error) {
+        self.destination.error(e);
+    }
+
+    fn complete(&mut self) {
+        self.destination.complete();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.destination.is_closed()
+    }
+}
+
+/// `SafeObserver` enforces the grammar of the events sent to its destination: any number of
+/// `next` events, optionally followed by a single `error` or `complete` event. No event is
+/// delivered once the destination terminated or the `subscription` has been unsubscribed, and a
+/// terminal event unsubscribes the `subscription`, releasing the resources of its source.
+pub struct SafeObserver<O> {
+    destination: O,
+    stopped: Arc<AtomicBool>,
+    subscription: Subscription,
+}
+
+impl<O> SafeObserver<O> {
+    pub fn new(destination: O, subscription: Subscription) -> SafeObserver<O> {
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let unsubscribed = stopped.clone();
+        subscription.add_teardown(move || unsubscribed.store(true, Ordering::SeqCst));
+
+        SafeObserver { destination, stopped, subscription }
+    }
+}
+
+impl<O> ObserverLike for SafeObserver<O> where O: ObserverLike {
+    type Value = O::Value;
+    type Error = O::Error;
+
+    fn next(&self, value: &Self::Value) {
+        if !self.stopped.load(Ordering::SeqCst) {
+            self.destination.next(value);
+        }
+    }
+
+    fn error(&self, e: &Self::Error) {
+        if !self.stopped.swap(true, Ordering::SeqCst) {
+            self.destination.error(e);
+            self.subscription.clone().unsubscribe();
+        }
+    }
+
+    fn complete(&mut self) {
+        if !self.stopped.swap(true, Ordering::SeqCst) {
+            self.destination.complete();
+            self.subscription.unsubscribe();
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst) || self.destination.is_closed()
+    }
+}
@@ -1,53 +1,391 @@
-use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
 
 use crate::error::RxError;
 use crate::observable::Unsubscriber;
-use crate::observer::ObserverLike;
+use crate::subscriber::Subscriber;
 
 pub trait Unsubscribable {
     fn unsubscribe(&mut self);
+
+    /// Wraps the subscription into a `SubscriptionGuard`, which unsubscribes once dropped.
+    fn into_guard(self) -> SubscriptionGuard<Self> where Self: Sized {
+        SubscriptionGuard::new(self)
+    }
+}
+
+/// `SubscriptionGuard` unsubscribes the subscription it owns once it's dropped, so that the
+/// execution of an `Observable` is bound to the lifetime of the guard.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rxrs::operators::interval;
+/// use rxrs::scheduler::NewThreadScheduler;
+/// use rxrs::subscription::Unsubscribable;
+///
+/// {
+///     let _guard = interval(Duration::from_millis(10), NewThreadScheduler::new())
+///         .subscribe_next(|value| println!("{}", value))
+///         .into_guard();
+/// } // the interval is stopped here
+/// ```
+pub struct SubscriptionGuard<U: Unsubscribable> {
+    subscription: U,
+}
+
+impl<U: Unsubscribable> SubscriptionGuard<U> {
+    pub fn new(subscription: U) -> SubscriptionGuard<U> {
+        SubscriptionGuard { subscription }
+    }
+}
+
+impl<U: Unsubscribable> Unsubscribable for SubscriptionGuard<U> {
+    fn unsubscribe(&mut self) {
+        self.subscription.unsubscribe();
+    }
+}
+
+impl<U: Unsubscribable> Drop for SubscriptionGuard<U> {
+    fn drop(&mut self) {
+        self.subscription.unsubscribe();
+    }
+}
+
+/// `DisposeBag` owns many subscriptions (of any kind), and unsubscribes all of them once it's
+/// dropped, e.g. together with the component that created them.
+#[derive(Default)]
+pub struct DisposeBag {
+    subscriptions: Vec<Box<dyn Unsubscribable + Send>>,
+}
+
+impl DisposeBag {
+    pub fn new() -> DisposeBag {
+        DisposeBag::default()
+    }
+
+    /// Adds a `subscription`, unsubscribed once the bag is dropped.
+    pub fn add<U>(&mut self, subscription: U) where U: Unsubscribable + Send + 'static {
+        self.subscriptions.push(Box::new(subscription));
+    }
+
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+}
+
+impl Unsubscribable for DisposeBag {
+    /// Unsubscribes and removes every subscription of the bag, which can be reused afterwards.
+    fn unsubscribe(&mut self) {
+        self.subscriptions.drain(..).for_each(|mut subscription| subscription.unsubscribe());
+    }
+}
+
+impl Drop for DisposeBag {
+    fn drop(&mut self) {
+        self.unsubscribe();
+    }
+}
+
+/// Teardown logic executed once a `Subscription` is unsubscribed.
+enum Teardown {
+    Callback(Box<dyn FnOnce() + Send>),
+    Child(Subscription),
+}
+
+struct SubscriptionState {
+    closed: bool,
+    teardowns: Vec<Teardown>,
 }
 
+/// `Subscription` represents the execution of an `Observable`, and can be unsubscribed to
+/// release its resources. A `Subscription` can group other subscriptions (its children) and
+/// teardown callbacks, which are executed in the order they have been added when the
+/// subscription is unsubscribed.
+///
+/// A `Subscription` is a handle: its clones refer to the same subscription, which can be used
+/// to `remove` a child from its parent.
+#[derive(Clone)]
 pub struct Subscription {
-    pub closed: bool,
-    unsubscriber: Unsubscriber,
+    state: Arc<Mutex<SubscriptionState>>,
 }
 
 impl Subscription {
-    pub fn new(unsubscriber: Unsubscriber) -> Subscription {
-        Subscription { closed: false, unsubscriber }
+    pub fn new(mut unsubscriber: Unsubscriber) -> Subscription {
+        let subscription = Subscription::empty();
+        subscription.add_teardown(move || unsubscriber.call());
+        subscription
+    }
+
+    /// Creates a `Subscription` without any teardown logic.
+    pub fn empty() -> Subscription {
+        Subscription { state: Arc::new(Mutex::new(SubscriptionState { closed: false, teardowns: Vec::new() })) }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+
+    /// Adds a `child` subscription, unsubscribed together with this subscription. If this
+    /// subscription is already closed, the child is unsubscribed right away. A child
+    /// unsubscribed on its own is removed from this subscription.
+    pub fn add(&self, mut child: Subscription) {
+        if Arc::ptr_eq(&self.state, &child.state) {
+            return;
+        }
+
+        if self.push(Teardown::Child(child.clone())).is_some() {
+            child.unsubscribe();
+            return;
+        }
+
+        // the references are weak, so that neither subscription is kept alive by the other
+        let (parent, added) = (Arc::downgrade(&self.state), Arc::downgrade(&child.state));
+        child.add_teardown(move || {
+            if let (Some(parent), Some(added)) = (parent.upgrade(), added.upgrade()) {
+                Subscription { state: parent }.remove(&Subscription { state: added });
+            }
+        });
+    }
+
+    /// Removes a `child` subscription previously added, without unsubscribing it.
+    pub fn remove(&self, child: &Subscription) {
+        self.state.lock().unwrap().teardowns.retain(|teardown| match teardown {
+            Teardown::Child(added) => !Arc::ptr_eq(&added.state, &child.state),
+            Teardown::Callback(_) => true
+        });
+    }
+
+    /// Adds a `teardown` callback, called once this subscription is unsubscribed. If this
+    /// subscription is already closed, the callback is called right away.
+    pub fn add_teardown<F>(&self, teardown: F) where F: FnOnce() + Send + 'static {
+        if let Some(Teardown::Callback(teardown)) = self.push(Teardown::Callback(Box::new(teardown))) {
+            teardown();
+        }
+    }
+
+    /// Stores the `teardown`, or gives it back if this subscription is already closed.
+    fn push(&self, teardown: Teardown) -> Option<Teardown> {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return Some(teardown);
+        }
+
+        state.teardowns.push(teardown);
+        None
     }
 }
 
 impl Unsubscribable for Subscription {
     fn unsubscribe(&mut self) {
-        if !self.closed {
-            self.unsubscriber.call();
-            self.closed = true;
+        let teardowns = {
+            let mut state = self.state.lock().unwrap();
+            if state.closed {
+                return;
+            }
+            state.closed = true;
+            std::mem::take(&mut state.teardowns)
+        };
+
+        // the teardowns are executed without the lock, so that they can use this subscription
+        teardowns.into_iter().for_each(|teardown| match teardown {
+            Teardown::Callback(callback) => callback(),
+            Teardown::Child(mut child) => child.unsubscribe(),
+        });
+    }
+}
+
+/// `SubjectObserver` is an observer registered in a `Subject`. It is shared between the subject
+/// and its `SubjectSubscription`, so that it can be unsubscribed while the subject is emitting.
+/// A pending observer doesn't receive any event, until the subject activates it.
+pub struct SubjectObserver<T, E = RxError> {
+    active: AtomicBool,
+    closed: AtomicBool,
+    observer: Mutex<Subscriber<'static, T, E>>,
+}
+
+impl<T, E> SubjectObserver<T, E> {
+    pub fn new(observer: Subscriber<'static, T, E>) -> SubjectObserver<T, E> {
+        SubjectObserver { active: AtomicBool::new(true), closed: AtomicBool::new(false), observer: Mutex::new(observer) }
+    }
+
+    pub(crate) fn pending(observer: Subscriber<'static, T, E>) -> SubjectObserver<T, E> {
+        SubjectObserver { active: AtomicBool::new(false), ..SubjectObserver::new(observer) }
+    }
+
+    pub(crate) fn activate(&self) {
+        self.active.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the observer receives the events of the subject.
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst) && !self.is_closed()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn observer(&self) -> MutexGuard<'_, Subscriber<'static, T, E>> {
+        self.observer.lock().unwrap()
+    }
+}
+
+/// `ObserverKey` identifies an observer registered in `SubjectObservers`: the index of its slot,
+/// and the generation of the slot when it was registered. A slot is reused once its observer is
+/// removed, with a new generation, so that a stale key doesn't match the observer registered
+/// afterwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ObserverKey {
+    index: usize,
+    generation: u64,
+}
+
+/// `ObserverSlot` is a slot of `SubjectObservers`. The occupied slots are linked in subscription
+/// order.
+struct ObserverSlot<T, E> {
+    generation: u64,
+    observer: Option<Arc<SubjectObserver<T, E>>>,
+    previous: Option<usize>,
+    next: Option<usize>,
+}
+
+/// `SubjectObservers` is the registry of the observers of a `Subject`. The observers are stored
+/// in slots, whose free list is reused by the next observers, so that registering and removing
+/// an observer costs a constant time. The observers are iterated in subscription order.
+pub struct SubjectObservers<T, E = RxError> {
+    slots: Vec<ObserverSlot<T, E>>,
+    free: Vec<usize>,
+    first: Option<usize>,
+    last: Option<usize>,
+    len: usize,
+}
+
+impl<T, E> Default for SubjectObservers<T, E> {
+    fn default() -> SubjectObservers<T, E> {
+        SubjectObservers { slots: Vec::new(), free: Vec::new(), first: None, last: None, len: 0 }
+    }
+}
+
+impl<T, E> SubjectObservers<T, E> {
+    pub fn new() -> SubjectObservers<T, E> {
+        SubjectObservers::default()
+    }
+
+    /// Registers an `observer`, and returns its key.
+    pub fn insert(&mut self, observer: Arc<SubjectObserver<T, E>>) -> ObserverKey {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(ObserverSlot { generation: 0, observer: None, previous: None, next: None });
+                self.slots.len() - 1
+            }
+        };
+
+        // the observer is linked after the last one
+        let slot = &mut self.slots[index];
+        slot.observer = Some(observer);
+        slot.previous = self.last;
+        slot.next = None;
+        let generation = slot.generation;
+
+        match self.last {
+            Some(last) => self.slots[last].next = Some(index),
+            None => self.first = Some(index),
         }
+        self.last = Some(index);
+        self.len += 1;
+
+        ObserverKey { index, generation }
+    }
+
+    /// Removes the observer registered with `key`, if it's still registered.
+    pub fn remove(&mut self, key: ObserverKey) -> Option<Arc<SubjectObserver<T, E>>> {
+        let slot = self.slots.get_mut(key.index).filter(|slot| slot.generation == key.generation)?;
+        let observer = slot.observer.take()?;
+        slot.generation += 1;
+        let (previous, next) = (slot.previous.take(), slot.next.take());
+
+        match previous {
+            Some(previous) => self.slots[previous].next = next,
+            None => self.first = next,
+        }
+        match next {
+            Some(next) => self.slots[next].previous = previous,
+            None => self.last = previous,
+        }
+        self.free.push(key.index);
+        self.len -= 1;
+
+        Some(observer)
+    }
+
+    /// Removes every observer.
+    pub fn clear(&mut self) {
+        while let Some(index) = self.first {
+            let generation = self.slots[index].generation;
+            self.remove(ObserverKey { index, generation });
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the registered observers, in subscription order.
+    pub fn iter(&self) -> impl Iterator<Item=&Arc<SubjectObserver<T, E>>> {
+        let mut current = self.first;
+        std::iter::from_fn(move || {
+            let slot = &self.slots[current?];
+            current = slot.next;
+            slot.observer.as_ref()
+        })
     }
 }
 
-pub type TrackedSubjectObservers<O> = RefCell<Vec<Option<O>>>;
+/// `TrackedSubjectObservers` is the registry of observers of a `Subject`, shared between the
+/// subject and its subscriptions.
+pub type TrackedSubjectObservers<T, E = RxError> = Arc<Mutex<SubjectObservers<T, E>>>;
 
-pub struct SubjectSubscription<'a, O> {
+pub struct SubjectSubscription<T, E = RxError> {
     pub closed: bool,
-    pub subject_ref: &'a TrackedSubjectObservers<O>,
-    pub item: usize,
+    pub subject_ref: TrackedSubjectObservers<T, E>,
+    pub key: ObserverKey,
 }
 
-impl<'a, T, O> SubjectSubscription<'a, O> where O: ObserverLike<Value=T, Error=RxError> {
-    pub fn new(subject_ref: &'a TrackedSubjectObservers<O>) -> SubjectSubscription<'a, O> {
-        let item = subject_ref.borrow().len() - 1;
-        SubjectSubscription { closed: false, subject_ref, item }
+impl<T, E> SubjectSubscription<T, E> {
+    pub fn new(subject_ref: &TrackedSubjectObservers<T, E>, key: ObserverKey) -> SubjectSubscription<T, E> {
+        SubjectSubscription { closed: false, subject_ref: subject_ref.clone(), key }
+    }
+
+    /// Returns an already closed subscription, for an observer that wasn't registered.
+    pub fn closed(subject_ref: &TrackedSubjectObservers<T, E>) -> SubjectSubscription<T, E> {
+        let key = ObserverKey { index: usize::MAX, generation: 0 };
+        SubjectSubscription { closed: true, subject_ref: subject_ref.clone(), key }
     }
 }
 
-impl<'a, T, O> Unsubscribable for SubjectSubscription<'a, O> where O: ObserverLike<Value=T, Error=RxError> {
+impl<T, E> Unsubscribable for SubjectSubscription<T, E> {
     fn unsubscribe(&mut self) {
         if !self.closed {
-            let mut observers = self.subject_ref.borrow_mut();
-            observers[self.item] = None;
+            // the observer is already removed if the subject stopped or has been unsubscribed
+            let observer = self.subject_ref.lock().unwrap().remove(self.key);
+
+            // the observer is closed as well, since the subject could be notifying it already
+            if let Some(observer) = observer {
+                observer.close();
+            }
             self.closed = true;
         }
     }
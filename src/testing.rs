@@ -0,0 +1,491 @@
+//! Marble-diagram testing, following the RxJS marble testing conventions. Every character of a
+//! marble diagram is a frame of virtual time (1 ms of a `TestScheduler`):
+//!
+//!  * `-`: a frame without events.
+//!  * `a`, `b`, ...: a `next` event, whose value is looked up in the given values.
+//!  * `|`: a `complete` event.
+//!  * `#`: an `error` event. Its message is `error`, unless another one is given (e.g. with
+//!    `cold_with_error` or `to_be_with_error`).
+//!  * `(ab)`: events emitted synchronously in the same frame (the group's characters still
+//!    advance the time).
+//!  * `^`: the subscription point (of a hot observable, or in a subscription diagram).
+//!  * `!`: the unsubscription point in a subscription diagram.
+//!
+//! Whitespaces are ignored.
+//!
+//! ```rust
+//! use rxrs::testing::MarbleTester;
+//!
+//! let tester = MarbleTester::new();
+//! let source = tester.cold("-a-b-|", &[('a', 1), ('b', 2)]);
+//!
+//! tester.expect_observable(source.observable().map(|value| value * 10))
+//!     .to_be("-a-b-|", &[('a', 10), ('b', 20)]);
+//! tester.expect_subscriptions(source.subscriptions())
+//!     .to_be("^----!");
+//!
+//! tester.flush();
+//! ```
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::error::RxError;
+use crate::observable::{Observable, ObservableLike, Unsubscriber};
+use crate::observer::{Observer, ObserverLike};
+use crate::scheduler::{Scheduler, TestScheduler};
+use crate::subscriber::Subscriber;
+use crate::subscription::Unsubscribable;
+
+/// Virtual time of a single frame of a marble diagram.
+const FRAME: Duration = Duration::from_millis(1);
+
+/// Message of the `#` error events, unless another one is given.
+const DEFAULT_ERROR: &str = "error";
+
+/// Maximum number of frames executed by `MarbleTester::flush`.
+const MAX_FRAMES: u64 = 750;
+
+/// `Notification` is an event of an `Observable`. Errors are represented by their message, which
+/// lets observables with any error type implementing `Display` be verified.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Notification<T> {
+    Next(T),
+    Error(String),
+    Complete,
+}
+
+/// `Recorded` is a `Notification` emitted in a given frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recorded<T> {
+    pub frame: u64,
+    pub notification: Notification<T>,
+}
+
+/// `SubscriptionLog` defines the frames in which an observable was subscribed and unsubscribed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubscriptionLog {
+    pub subscribed: u64,
+    pub unsubscribed: Option<u64>,
+}
+
+impl fmt::Display for SubscriptionLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}^", "-".repeat(self.subscribed as usize))?;
+        if let Some(unsubscribed) = self.unsubscribed {
+            write!(f, "{}!", "-".repeat((unsubscribed - self.subscribed).saturating_sub(1) as usize))?;
+        }
+        Ok(())
+    }
+}
+
+/// `SubscriptionLogs` is the list of subscriptions of a cold or hot test observable.
+#[derive(Clone, Default)]
+pub struct SubscriptionLogs {
+    logs: Arc<Mutex<Vec<SubscriptionLog>>>,
+}
+
+impl SubscriptionLogs {
+    /// Logs a new subscription and returns its index.
+    fn subscribe(&self, frame: u64) -> usize {
+        let mut logs = self.logs.lock().unwrap();
+        logs.push(SubscriptionLog { subscribed: frame, unsubscribed: None });
+        logs.len() - 1
+    }
+
+    fn unsubscribe(&self, index: usize, frame: u64) {
+        self.logs.lock().unwrap()[index].unsubscribed = Some(frame);
+    }
+
+    pub fn get(&self) -> Vec<SubscriptionLog> {
+        self.logs.lock().unwrap().clone()
+    }
+}
+
+type Action = Box<dyn FnOnce()>;
+type Expectation = Box<dyn FnOnce() -> Result<(), String>>;
+
+/// `MarbleTester` creates cold and hot observables from marble diagrams and verifies the events
+/// of observables against marble diagrams, on a shared `TestScheduler`. Subscriptions and
+/// assertions are executed once `flush` is called.
+pub struct MarbleTester {
+    scheduler: TestScheduler,
+    actions: RefCell<Vec<(u64, Action)>>,
+    expectations: RefCell<Vec<Expectation>>,
+}
+
+impl Default for MarbleTester {
+    fn default() -> MarbleTester {
+        MarbleTester::new()
+    }
+}
+
+impl MarbleTester {
+    pub fn new() -> MarbleTester {
+        MarbleTester {
+            scheduler: TestScheduler::new(),
+            actions: RefCell::new(Vec::new()),
+            expectations: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns the `TestScheduler` of the tester, which should be used by the time-based
+    /// operators under test.
+    pub fn scheduler(&self) -> TestScheduler {
+        self.scheduler.clone()
+    }
+
+    /// Creates a cold observable, which emits the events of the `marbles` diagram relative to the
+    /// frame in which it's subscribed.
+    pub fn cold<T>(&self, marbles: &str, values: &[(char, T)]) -> ColdObservable<T> where T: Clone {
+        self.cold_with_error(marbles, values, DEFAULT_ERROR)
+    }
+
+    /// Creates a cold observable like `cold`, whose `#` error events have the `error` message.
+    pub fn cold_with_error<T>(&self, marbles: &str, values: &[(char, T)], error: &str) -> ColdObservable<T> where T: Clone {
+        let (messages, subscription) = parse_marbles(marbles, values, error);
+        assert!(subscription.is_none(), "cold observables can't have a subscription point");
+
+        ColdObservable {
+            messages: Arc::new(messages.into_iter().map(|(frame, notification)| (frame as u64, notification)).collect()),
+            scheduler: self.scheduler.clone(),
+            subscriptions: SubscriptionLogs::default(),
+        }
+    }
+
+    /// Creates a hot observable, which emits the events of the `marbles` diagram regardless of
+    /// its subscriptions. The frame of the subscription point `^` is the frame 0.
+    pub fn hot<T>(&self, marbles: &str, values: &[(char, T)]) -> HotObservable<T>
+        where T: Clone + Send + 'static {
+        self.hot_with_error(marbles, values, DEFAULT_ERROR)
+    }
+
+    /// Creates a hot observable like `hot`, whose `#` error events have the `error` message.
+    pub fn hot_with_error<T>(&self, marbles: &str, values: &[(char, T)], error: &str) -> HotObservable<T>
+        where T: Clone + Send + 'static {
+        let (messages, subscription) = parse_marbles(marbles, values, error);
+        let offset = subscription.unwrap_or(0);
+
+        let observers: HotObservers<T> = Arc::new(Mutex::new(Vec::new()));
+        for (frame, notification) in messages {
+            let frame = frame - offset;
+            if frame < 0 {
+                continue;
+            }
+
+            let observers = observers.clone();
+            self.scheduler.schedule_after(FRAME * frame as u32, Box::new(move || {
+                // the observers are copied, since a terminal event unsubscribes them
+                let current: Vec<HotObserver<T>> = observers.lock().unwrap().iter().flatten().cloned().collect();
+                for observer in current {
+                    notify(&mut observer.lock().unwrap(), &notification);
+                }
+            }));
+        }
+
+        HotObservable { observers, scheduler: self.scheduler.clone(), subscriptions: SubscriptionLogs::default() }
+    }
+
+    /// Subscribes to the `observable` in the first frame and records its events, to be verified
+    /// against a marble diagram.
+    pub fn expect_observable<T, E>(&self, observable: Observable<'static, T, E>) -> ExpectObservable<'_, T>
+        where T: Clone + Send + 'static,
+              E: fmt::Display + 'static {
+        self.expect_observable_with(observable, "^")
+    }
+
+    /// Subscribes to the `observable` (and unsubscribes from it) as defined by the `subscription`
+    /// diagram, and records its events, to be verified against a marble diagram.
+    pub fn expect_observable_with<T, E>(&self, observable: Observable<'static, T, E>, subscription: &str) -> ExpectObservable<'_, T>
+        where T: Clone + Send + 'static,
+              E: fmt::Display + 'static {
+        let log = parse_subscription(subscription);
+
+        let recorder = Recorder { records: Arc::new(Mutex::new(Vec::new())), scheduler: self.scheduler.clone() };
+        let (next, error, complete) = (recorder.clone(), recorder.clone(), recorder.clone());
+        let observer = Observer::new(
+            move |value: &T| next.record(Notification::Next(value.clone())),
+            move |e: &E| error.record(Notification::Error(e.to_string())),
+            move || complete.record(Notification::Complete),
+        );
+
+        let subscription = Rc::new(RefCell::new(None));
+        let subscribe_subscription = subscription.clone();
+        self.add_action(log.subscribed, move || {
+            *subscribe_subscription.borrow_mut() = Some(observable.subscribe(observer));
+        });
+        if let Some(unsubscribed) = log.unsubscribed {
+            self.add_action(unsubscribed, move || {
+                if let Some(mut subscription) = subscription.borrow_mut().take() {
+                    subscription.unsubscribe();
+                }
+            });
+        }
+
+        ExpectObservable { tester: self, records: recorder.records }
+    }
+
+    /// Records the `subscriptions` of a cold or hot observable, to be verified against
+    /// subscription diagrams.
+    pub fn expect_subscriptions(&self, subscriptions: SubscriptionLogs) -> ExpectSubscriptions<'_> {
+        ExpectSubscriptions { tester: self, subscriptions }
+    }
+
+    /// Executes the scheduled subscriptions and events (up to 750 frames), and verifies every
+    /// expectation. Panics if any expectation isn't fulfilled.
+    pub fn flush(&self) {
+        let mut actions = self.actions.replace(Vec::new());
+        actions.sort_by_key(|(frame, _)| *frame);
+
+        for (action_frame, action) in actions {
+            self.scheduler.advance_before(FRAME * action_frame as u32);
+            action();
+        }
+        self.scheduler.advance_to(FRAME * MAX_FRAMES as u32);
+
+        let failures: Vec<String> = self.expectations.replace(Vec::new()).into_iter()
+            .filter_map(|expectation| expectation().err())
+            .collect();
+
+        if !failures.is_empty() {
+            panic!("marble expectations failed:\n{}", failures.join("\n"));
+        }
+    }
+
+    fn add_action<F>(&self, frame: u64, action: F) where F: FnOnce() + 'static {
+        self.actions.borrow_mut().push((frame, Box::new(action)));
+    }
+
+    fn add_expectation<F>(&self, expectation: F) where F: FnOnce() -> Result<(), String> + 'static {
+        self.expectations.borrow_mut().push(Box::new(expectation));
+    }
+}
+
+impl Drop for MarbleTester {
+    fn drop(&mut self) {
+        if !std::thread::panicking() && !self.expectations.borrow().is_empty() {
+            panic!("marble expectations were never verified, `MarbleTester::flush` must be called");
+        }
+    }
+}
+
+/// `ColdObservable` emits the events of a marble diagram relative to each subscription.
+pub struct ColdObservable<T> {
+    messages: Arc<Vec<(u64, Notification<T>)>>,
+    scheduler: TestScheduler,
+    subscriptions: SubscriptionLogs,
+}
+
+impl<T> ColdObservable<T> where T: Clone + Send + Sync + 'static {
+    /// Creates an `Observable` of the diagram's events. Every subscription logs to the same
+    /// `SubscriptionLogs`.
+    pub fn observable(&self) -> Observable<'static, T> {
+        let (messages, scheduler, subscriptions) = (self.messages.clone(), self.scheduler.clone(), self.subscriptions.clone());
+
+        Observable::new(move |subscriber: Subscriber<'static, T>| {
+            let index = subscriptions.subscribe(frame(&scheduler));
+            let subscriber = Arc::new(Mutex::new(subscriber));
+
+            let handles: Vec<_> = messages.iter().cloned()
+                .map(|(frame, notification)| {
+                    let subscriber = subscriber.clone();
+                    scheduler.schedule_after(FRAME * frame as u32, Box::new(move || {
+                        notify(&mut subscriber.lock().unwrap(), &notification);
+                    }))
+                })
+                .collect();
+
+            let (scheduler, subscriptions) = (scheduler.clone(), subscriptions.clone());
+            Unsubscriber::new(move || {
+                handles.iter().for_each(|handle| handle.cancel());
+                subscriptions.unsubscribe(index, frame(&scheduler));
+            })
+        })
+    }
+
+    pub fn subscriptions(&self) -> SubscriptionLogs {
+        self.subscriptions.clone()
+    }
+}
+
+type HotObserver<T> = Arc<Mutex<Subscriber<'static, T>>>;
+type HotObservers<T> = Arc<Mutex<Vec<Option<HotObserver<T>>>>>;
+
+/// `HotObservable` emits the events of a marble diagram to its current subscribers.
+pub struct HotObservable<T> {
+    observers: HotObservers<T>,
+    scheduler: TestScheduler,
+    subscriptions: SubscriptionLogs,
+}
+
+impl<T> HotObservable<T> where T: 'static {
+    /// Creates an `Observable` subscribing to the diagram's events. Every subscription logs to
+    /// the same `SubscriptionLogs`.
+    pub fn observable(&self) -> Observable<'static, T> {
+        let (observers, scheduler, subscriptions) = (self.observers.clone(), self.scheduler.clone(), self.subscriptions.clone());
+
+        Observable::new(move |subscriber: Subscriber<'static, T>| {
+            let index = subscriptions.subscribe(frame(&scheduler));
+            let item = {
+                let mut observers = observers.lock().unwrap();
+                observers.push(Some(Arc::new(Mutex::new(subscriber))));
+                observers.len() - 1
+            };
+
+            let (observers, scheduler, subscriptions) = (observers.clone(), scheduler.clone(), subscriptions.clone());
+            Unsubscriber::new(move || {
+                observers.lock().unwrap()[item] = None;
+                subscriptions.unsubscribe(index, frame(&scheduler));
+            })
+        })
+    }
+
+    pub fn subscriptions(&self) -> SubscriptionLogs {
+        self.subscriptions.clone()
+    }
+}
+
+/// `Recorder` records the events of an observable, in the frame they are emitted.
+#[derive(Clone)]
+struct Recorder<T> {
+    records: Arc<Mutex<Vec<Recorded<T>>>>,
+    scheduler: TestScheduler,
+}
+
+impl<T> Recorder<T> {
+    fn record(&self, notification: Notification<T>) {
+        self.records.lock().unwrap().push(Recorded { frame: frame(&self.scheduler), notification });
+    }
+}
+
+/// `ExpectObservable` verifies the recorded events of an observable against a marble diagram.
+pub struct ExpectObservable<'t, T> {
+    tester: &'t MarbleTester,
+    records: Arc<Mutex<Vec<Recorded<T>>>>,
+}
+
+impl<'t, T> ExpectObservable<'t, T> where T: Clone + PartialEq + fmt::Debug + 'static {
+    /// Expects the observable to emit the events of the `marbles` diagram, whose values are
+    /// looked up in `values`. It's verified once the `MarbleTester` is flushed.
+    pub fn to_be(self, marbles: &str, values: &[(char, T)]) {
+        self.to_be_with_error(marbles, values, DEFAULT_ERROR);
+    }
+
+    /// Expects the observable to emit the events of the `marbles` diagram like `to_be`, where
+    /// the `#` error event is expected to be displayed as the `error` message.
+    pub fn to_be_with_error(self, marbles: &str, values: &[(char, T)], error: &str) {
+        let (messages, _) = parse_marbles(marbles, values, error);
+        let expected: Vec<Recorded<T>> = messages.into_iter()
+            .map(|(frame, notification)| Recorded { frame: frame as u64, notification })
+            .collect();
+
+        let records = self.records;
+        self.tester.add_expectation(move || {
+            let actual = records.lock().unwrap().clone();
+            if actual == expected {
+                Ok(())
+            } else {
+                Err(format!("expected events {:?}\n     but got {:?}", expected, actual))
+            }
+        });
+    }
+}
+
+/// `ExpectSubscriptions` verifies the subscriptions of an observable against subscription
+/// diagrams.
+pub struct ExpectSubscriptions<'t> {
+    tester: &'t MarbleTester,
+    subscriptions: SubscriptionLogs,
+}
+
+impl<'t> ExpectSubscriptions<'t> {
+    /// Expects a single subscription, as defined by the `marbles` subscription diagram.
+    pub fn to_be(self, marbles: &str) {
+        self.to_be_all(&[marbles]);
+    }
+
+    /// Expects a subscription for every subscription diagram of `marbles`, in the same order.
+    pub fn to_be_all(self, marbles: &[&str]) {
+        let expected: Vec<SubscriptionLog> = marbles.iter().map(|marbles| parse_subscription(marbles)).collect();
+
+        let subscriptions = self.subscriptions;
+        self.tester.add_expectation(move || {
+            let actual = subscriptions.get();
+            if actual == expected {
+                Ok(())
+            } else {
+                let format = |logs: &[SubscriptionLog]| logs.iter().map(|log| log.to_string()).collect::<Vec<String>>();
+                Err(format!("expected subscriptions {:?}\n            but got {:?}", format(&expected), format(&actual)))
+            }
+        });
+    }
+}
+
+/// Returns the current frame of the `scheduler`.
+fn frame(scheduler: &TestScheduler) -> u64 {
+    (scheduler.elapsed().as_nanos() / FRAME.as_nanos()) as u64
+}
+
+fn notify<T>(subscriber: &mut Subscriber<'static, T>, notification: &Notification<T>) {
+    match notification {
+        Notification::Next(value) => subscriber.next(value),
+        Notification::Error(message) => subscriber.error(&RxError::CustomError(message.clone())),
+        Notification::Complete => subscriber.complete(),
+    }
+}
+
+/// Parses a marble diagram into its events and the frame of its subscription point (if any).
+fn parse_marbles<T: Clone>(marbles: &str, values: &[(char, T)], error: &str) -> (Vec<(i64, Notification<T>)>, Option<i64>) {
+    let mut messages = Vec::new();
+    let mut subscription = None;
+    let mut group: Option<i64> = None;
+
+    let characters = marbles.chars().filter(|character| !character.is_whitespace());
+    for (frame, character) in characters.enumerate() {
+        let frame = group.unwrap_or(frame as i64);
+        match character {
+            '-' => {}
+            '(' => {
+                assert!(group.is_none(), "nested groups are not supported: {}", marbles);
+                group = Some(frame);
+            }
+            ')' => group = None,
+            '^' => subscription = Some(frame),
+            '|' => messages.push((frame, Notification::Complete)),
+            '#' => messages.push((frame, Notification::Error(String::from(error)))),
+            value => {
+                let (_, value) = values.iter()
+                    .find(|(key, _)| *key == value)
+                    .unwrap_or_else(|| panic!("no value defined for '{}' in {}", value, marbles));
+                messages.push((frame, Notification::Next(value.clone())));
+            }
+        }
+    }
+    (messages, subscription)
+}
+
+/// Parses a subscription diagram (e.g. `--^---!`) into a `SubscriptionLog`.
+fn parse_subscription(marbles: &str) -> SubscriptionLog {
+    let mut subscribed = None;
+    let mut unsubscribed = None;
+
+    let characters = marbles.chars().filter(|character| !character.is_whitespace());
+    for (frame, character) in characters.enumerate() {
+        match character {
+            '-' => {}
+            '^' => subscribed = Some(frame as u64),
+            '!' => {
+                assert!(subscribed.is_some(), "unsubscription point before the subscription point in {}", marbles);
+                unsubscribed = Some(frame as u64);
+            }
+            other => panic!("unexpected character '{}' in subscription diagram {}", other, marbles),
+        }
+    }
+
+    SubscriptionLog {
+        subscribed: subscribed.unwrap_or_else(|| panic!("no subscription point in {}", marbles)),
+        unsubscribed,
+    }
+}
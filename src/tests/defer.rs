@@ -0,0 +1,22 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::operators::{defer, from_vec};
+use crate::tests::utils::{is_completed, values_sent};
+
+#[test]
+fn create_per_subscription() {
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let counter = calls.clone();
+    let obs = defer(move || {
+        let call = counter.fetch_add(1, Ordering::SeqCst);
+        from_vec(vec![call, call * 10])
+    });
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+    assert!(values_sent(&obs, &[0, 0]));
+    assert!(values_sent(&obs, &[1, 10]));
+    assert!(is_completed(&obs));
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
@@ -1,12 +1,19 @@
-use std::time::Instant;
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::operators::of;
+use crate::error::RxError;
+use crate::operators::{empty, from_vec, interval, just, of, throw_error, timer};
+use crate::scheduler::{ImmediateScheduler, NewThreadScheduler, PeriodicTask, ScheduleHandle, Scheduler, Task, TestScheduler, ThreadPoolScheduler};
+use crate::subscription::{Subscription, Unsubscribable};
+use crate::testing::MarbleTester;
 use crate::tests::utils::{is_completed, values_sent};
 
 #[test]
 fn delay_send_all_values() {
     let obs = of(&[1, 2, 3])
-        .delay(0);
+        .delay(Duration::from_millis(0), ImmediateScheduler::new());
 
 
     assert!(values_sent(&obs, &[1, 2, 3]));
@@ -15,25 +22,218 @@ fn delay_send_all_values() {
 
 #[test]
 fn delay_time_on_next() {
+    let scheduler = TestScheduler::new();
     let obs = of(&[1, 2, 3])
-        .delay(200)
+        .delay(Duration::from_millis(200), scheduler.clone())
         .filter(|value| value == &1); // `subscribe_next` triggers only once
 
-    let start = Instant::now();
-    obs.subscribe_next(move |_| {
-        let diff = start.elapsed().as_millis();
-        assert_eq!(diff, 200);
+    let emitted = Arc::new(Mutex::new(Vec::new()));
+    let (next_emitted, next_scheduler) = (emitted.clone(), scheduler.clone());
+    obs.subscribe_next(move |value| {
+        next_emitted.lock().unwrap().push((*value, next_scheduler.elapsed()));
     });
+
+    scheduler.advance_by(Duration::from_millis(199));
+    assert!(emitted.lock().unwrap().is_empty());
+
+    scheduler.advance_by(Duration::from_millis(1));
+    assert_eq!(*emitted.lock().unwrap(), vec![(1, Duration::from_millis(200))]);
 }
 
 #[test]
 fn delay_time_on_complete() {
+    let scheduler = TestScheduler::new();
     let obs = of(&[1, 2, 3])
-        .delay(200);
+        .delay(Duration::from_millis(200), scheduler.clone());
 
-    let start = Instant::now();
+    let completed = Arc::new(Mutex::new(None));
+    let (complete_completed, complete_scheduler) = (completed.clone(), scheduler.clone());
     obs.subscribe_complete(move || {
-        let diff = start.elapsed().as_millis();
-        assert_eq!(diff, 600);
+        *complete_completed.lock().unwrap() = Some(complete_scheduler.elapsed());
+    });
+
+    scheduler.flush();
+    assert_eq!(*completed.lock().unwrap(), Some(Duration::from_millis(200)));
+}
+
+#[test]
+fn delay_time_on_error() {
+    let scheduler = TestScheduler::new();
+    let obs = throw_error::<i32, _>(RxError::Timeout)
+        .delay(Duration::from_millis(200), scheduler.clone());
+
+    let failed = Arc::new(Mutex::new(None));
+    let (error_failed, error_scheduler) = (failed.clone(), scheduler.clone());
+    obs.subscribe_error(move |e| {
+        *error_failed.lock().unwrap() = Some((e.to_string(), error_scheduler.elapsed()));
+    });
+
+    scheduler.advance_by(Duration::from_millis(199));
+    assert!(failed.lock().unwrap().is_none());
+
+    scheduler.flush();
+    assert_eq!(*failed.lock().unwrap(), Some((String::from("timeout"), Duration::from_millis(200))));
+}
+
+#[test]
+fn delay_keep_spacing() {
+    let tester = MarbleTester::new();
+    let source = tester.cold("-a--b-#", &[('a', 1), ('b', 2)]);
+
+    let result = source.observable().delay(Duration::from_millis(3), tester.scheduler());
+
+    tester.expect_observable(result).to_be("----a--b-#", &[('a', 1), ('b', 2)]);
+    tester.flush();
+}
+
+#[test]
+fn delay_cancel_on_unsubscribe() {
+    let scheduler = TestScheduler::new();
+    let obs = of(&[1, 2, 3])
+        .delay(Duration::from_millis(200), scheduler.clone());
+
+    let emitted = Arc::new(Mutex::new(Vec::new()));
+    let next_emitted = emitted.clone();
+    let mut subscription = obs.subscribe_next(move |value| next_emitted.lock().unwrap().push(*value));
+
+    scheduler.advance_by(Duration::from_millis(100));
+    subscription.unsubscribe();
+
+    scheduler.flush();
+    assert!(emitted.lock().unwrap().is_empty());
+    assert_eq!(scheduler.elapsed(), Duration::from_millis(100));
+}
+
+#[test]
+fn delay_when() {
+    let tester = MarbleTester::new();
+    let source = tester.cold("-a-b-c|", &[('a', 3), ('b', 0), ('c', 2)]);
+
+    let scheduler = tester.scheduler();
+    let result = source.observable()
+        .delay_when(move |value| timer(Duration::from_millis(*value), scheduler.clone()));
+
+    tester.expect_observable(result).to_be("---ba--(c|)", &[('a', 3), ('b', 0), ('c', 2)]);
+    tester.flush();
+}
+
+#[test]
+fn delay_when_empty_delay() {
+    let obs = of(&[1, 2, 3])
+        .delay_when(|value| if value % 2 == 0 { empty() } else { just(()) });
+
+    assert!(values_sent(&obs, &[1, 3]));
+    assert!(is_completed(&obs));
+}
+
+fn delivery_order<S>(scheduler: S) -> Vec<String> where S: Scheduler + Clone + 'static {
+    let obs = from_vec((0..50).collect())
+        .delay(Duration::from_millis(5), scheduler);
+
+    let (tx, rx) = channel();
+    let complete_tx = tx.clone();
+    obs.subscribe_all(
+        move |value| tx.send(value.to_string()).unwrap(),
+        |_| {},
+        move || complete_tx.send(String::from("complete")).unwrap(),
+    );
+
+    let mut events = Vec::new();
+    while let Ok(event) = rx.recv_timeout(Duration::from_secs(1)) {
+        events.push(event);
+        if events.last().unwrap() == "complete" {
+            break;
+        }
+    }
+    events
+}
+
+fn expected_order() -> Vec<String> {
+    (0..50).map(|value| value.to_string()).chain(vec![String::from("complete")]).collect()
+}
+
+#[test]
+fn delay_order_new_thread() {
+    assert_eq!(delivery_order(NewThreadScheduler::new()), expected_order());
+}
+
+#[test]
+fn delay_order_thread_pool() {
+    assert_eq!(delivery_order(ThreadPoolScheduler::new(4)), expected_order());
+}
+
+/// `TrackingScheduler` executes the tasks of a `NewThreadScheduler` joining its threads on
+/// cancel, and keeps their handles.
+#[derive(Clone)]
+struct TrackingScheduler {
+    scheduler: NewThreadScheduler,
+    handles: Arc<Mutex<Vec<ScheduleHandle>>>,
+}
+
+impl TrackingScheduler {
+    fn track(&self, handle: ScheduleHandle) -> ScheduleHandle {
+        self.handles.lock().unwrap().push(handle.clone());
+        handle
+    }
+}
+
+impl Scheduler for TrackingScheduler {
+    fn now(&self) -> Instant {
+        self.scheduler.now()
+    }
+
+    fn schedule(&self, task: Task) -> ScheduleHandle {
+        self.track(self.scheduler.schedule(task))
+    }
+
+    fn schedule_after(&self, delay: Duration, task: Task) -> ScheduleHandle {
+        self.track(self.scheduler.schedule_after(delay, task))
+    }
+
+    fn schedule_periodic(&self, initial: Duration, period: Duration, task: PeriodicTask) -> ScheduleHandle {
+        self.track(self.scheduler.schedule_periodic(initial, period, task))
+    }
+}
+
+#[test]
+fn delay_join_on_cancel() {
+    let scheduler = TrackingScheduler {
+        scheduler: NewThreadScheduler::join_on_cancel(),
+        handles: Arc::new(Mutex::new(Vec::new())),
+    };
+
+    // the completion cancels the pending deliveries (joining their threads) while it's delivered
+    for _ in 0..20 {
+        assert_eq!(delivery_order(scheduler.clone()), expected_order());
+    }
+
+    let (tx, rx) = channel();
+    let handles = std::mem::take(&mut *scheduler.handles.lock().unwrap());
+    thread::spawn(move || {
+        handles.iter().for_each(ScheduleHandle::join);
+        tx.send(()).unwrap();
+    });
+    assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+}
+
+#[test]
+fn delay_unsubscribe_on_next() {
+    let scheduler = NewThreadScheduler::join_on_cancel();
+    let obs = interval(Duration::from_millis(1), scheduler)
+        .delay(Duration::from_millis(5), scheduler);
+
+    let (tx, rx) = channel();
+    let subscription: Arc<Mutex<Option<Subscription>>> = Arc::new(Mutex::new(None));
+    let next_subscription = subscription.clone();
+    let created = obs.subscribe_next(move |value| {
+        if *value == 2 {
+            if let Some(mut subscription) = next_subscription.lock().unwrap().take() {
+                subscription.unsubscribe();
+                tx.send(()).unwrap();
+            }
+        }
     });
+    subscription.lock().unwrap().replace(created);
+
+    assert!(rx.recv_timeout(Duration::from_secs(1)).is_ok());
 }
\ No newline at end of file
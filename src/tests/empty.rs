@@ -0,0 +1,10 @@
+use crate::operators::empty;
+use crate::tests::utils::{is_completed, values_sent};
+
+#[test]
+fn create() {
+    let obs = empty::<i32>();
+
+    assert!(values_sent(&obs, &[]));
+    assert!(is_completed(&obs));
+}
@@ -0,0 +1,48 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::error::RxError;
+
+#[derive(Debug)]
+struct ConnectionError;
+
+impl fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "connection refused")
+    }
+}
+
+impl Error for ConnectionError {}
+
+#[test]
+fn display() {
+    assert_eq!(RxError::CustomError(String::from("custom")).to_string(), "custom");
+    assert_eq!(RxError::Timeout.to_string(), "timeout");
+    assert_eq!(RxError::EmptySequence.to_string(), "sequence contains no elements");
+    assert_eq!(RxError::ArgumentOutOfRange(String::from("index 3")).to_string(), "argument out of range: index 3");
+}
+
+#[test]
+fn composite() {
+    let error = RxError::Composite(vec![RxError::Timeout, RxError::Unsubscribed]);
+
+    assert_eq!(error.to_string(), "2 errors occurred: [timeout] [already unsubscribed]");
+    assert!(error.source().is_none());
+}
+
+#[test]
+fn source() {
+    let error = RxError::from_error(ConnectionError);
+
+    assert_eq!(error.to_string(), "source error");
+    assert_eq!(error.source().map(|source| source.to_string()), Some(String::from("connection refused")));
+    assert!(matches!(error.clone(), RxError::Source(_)));
+}
+
+#[test]
+fn from_boxed() {
+    let boxed: Box<dyn Error + Send + Sync> = Box::new(ConnectionError);
+    let error = RxError::from(boxed);
+
+    assert!(error.source().unwrap().is::<ConnectionError>());
+}
@@ -17,7 +17,6 @@ fn filter_data() {
     struct DataItem {
         value: i32
     }
-    ;
 
     let data = [
         DataItem { value: 1 },
@@ -33,4 +32,14 @@ fn filter_data() {
         DataItem { value: 3 }
     ]));
     assert!(is_completed(&obs));
-}
\ No newline at end of file
+}
+
+#[test]
+fn filter_captured_state() {
+    let allowed = [1, 3];
+    let obs = of(&[1, 2, 3])
+        .filter(move |item| allowed.contains(item));
+
+    assert!(values_sent(&obs, &[1, 3]));
+    assert!(is_completed(&obs));
+}
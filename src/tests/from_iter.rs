@@ -0,0 +1,58 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use crate::observable::Observable;
+use crate::operators::{from_iter, from_vec};
+use crate::scheduler::TestScheduler;
+use crate::tests::utils::{is_completed, values_sent};
+
+fn owned_values() -> Observable<'static, String> {
+    let values = vec![String::from("a"), String::from("b")];
+    from_vec(values)
+}
+
+#[test]
+fn create_from_iter() {
+    let obs = from_iter((1..4).map(|value| value * 10));
+
+    assert!(values_sent(&obs, &[10, 20, 30]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn create_from_collection() {
+    let set: BTreeSet<i32> = [3, 1, 2].iter().cloned().collect();
+    let obs = from_iter(set);
+
+    assert!(values_sent(&obs, &[1, 2, 3]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn iterate_per_subscription() {
+    let obs = from_iter((0..3).map(|value| value * 2));
+
+    // every subscription iterates from the beginning
+    assert!(values_sent(&obs, &[0, 2, 4]));
+    assert!(values_sent(&obs, &[0, 2, 4]));
+}
+
+#[test]
+fn create_from_vec() {
+    let obs = owned_values();
+
+    assert!(values_sent(&obs, &[String::from("a"), String::from("b")]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn from_vec_threaded() {
+    let scheduler = TestScheduler::new();
+    let obs = owned_values().delay(Duration::from_millis(10), scheduler.clone());
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    obs.subscribe_next(move |value| tx.send(value.clone()).unwrap());
+
+    scheduler.flush();
+    assert_eq!(rx.try_iter().collect::<Vec<String>>(), vec!["a", "b"]);
+}
@@ -0,0 +1,40 @@
+use std::sync::mpsc::channel;
+
+use crate::operators::generate;
+use crate::scheduler::{ImmediateScheduler, TestScheduler};
+use crate::subscription::Unsubscribable;
+use crate::tests::utils::{is_completed, values_sent};
+
+#[test]
+fn create() {
+    let obs = generate(1, |value| *value < 10, |value| value * 2, ImmediateScheduler::new());
+
+    assert!(values_sent(&obs, &[1, 2, 4, 8]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn scheduled() {
+    let scheduler = TestScheduler::new();
+    let obs = generate(0, |value| *value < 3, |value| value + 1, scheduler.clone());
+
+    let (tx, rx) = channel();
+    obs.subscribe_next(move |value| tx.send(*value).unwrap());
+    assert!(rx.try_recv().is_err());
+
+    scheduler.flush();
+    assert_eq!(rx.try_iter().collect::<Vec<i32>>(), vec![0, 1, 2]);
+}
+
+#[test]
+fn unsubscribe() {
+    let scheduler = TestScheduler::new();
+    let obs = generate(0, |_| true, |value| value + 1, scheduler.clone());
+
+    let (tx, rx) = channel();
+    let mut subscription = obs.subscribe_next(move |value| tx.send(*value).unwrap());
+    subscription.unsubscribe();
+
+    scheduler.flush();
+    assert!(rx.try_recv().is_err());
+}
@@ -1,14 +1,84 @@
-use std::thread::sleep;
-use std::time::Duration;
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::operators::interval;
+use crate::scheduler::{NewThreadScheduler, TestScheduler};
+use crate::subscription::{Subscription, Unsubscribable};
 use crate::tests::utils::{is_completed, values_sent};
 
 #[test]
 fn create() {
-    let obs = interval(1);
+    let obs = interval(Duration::from_millis(1), NewThreadScheduler::new());
 
-    sleep(Duration::from_millis(2));
     assert!(values_sent(&obs, &[0, 1, 2]));
-    assert_eq!(is_completed(&obs), false);
+    assert!(!is_completed(&obs));
+}
+
+#[test]
+fn emit_on_period() {
+    let scheduler = TestScheduler::new();
+    let obs = interval(Duration::from_millis(10), scheduler.clone());
+
+    let emitted = Arc::new(Mutex::new(Vec::new()));
+    let (next_emitted, next_scheduler) = (emitted.clone(), scheduler.clone());
+    let mut subscription = obs.subscribe_next(move |value| {
+        next_emitted.lock().unwrap().push((*value, next_scheduler.elapsed().as_millis()));
+    });
+
+    scheduler.advance_by(Duration::from_millis(35));
+    assert_eq!(*emitted.lock().unwrap(), vec![(0, 10), (1, 20), (2, 30)]);
+
+    subscription.unsubscribe();
+    scheduler.advance_by(Duration::from_millis(100));
+    assert_eq!(emitted.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn stop_on_unsubscribe() {
+    let obs = interval(Duration::from_millis(1), NewThreadScheduler::join_on_cancel());
+
+    let emitted = Arc::new(Mutex::new(Vec::new()));
+    let next_emitted = emitted.clone();
+    let mut subscription = obs.subscribe_next(move |value| next_emitted.lock().unwrap().push(*value));
+
+    while emitted.lock().unwrap().len() < 3 {
+        thread::sleep(Duration::from_millis(1));
+    }
+    subscription.unsubscribe();
+
+    let count = emitted.lock().unwrap().len();
+    thread::sleep(Duration::from_millis(10));
+    assert_eq!(emitted.lock().unwrap().len(), count);
+}
+
+#[test]
+fn unsubscribe_without_waiting_period() {
+    let obs = interval(Duration::from_secs(60), NewThreadScheduler::join_on_cancel());
+    let mut subscription = obs.subscribe_next(|value| println!("{}", value));
+
+    let start = Instant::now();
+    subscription.unsubscribe();
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn unsubscribe_on_next() {
+    let obs = interval(Duration::from_millis(1), NewThreadScheduler::join_on_cancel());
+
+    let (tx, rx) = channel();
+    let subscription: Arc<Mutex<Option<Subscription>>> = Arc::new(Mutex::new(None));
+    let next_subscription = subscription.clone();
+    let created = obs.subscribe_next(move |value| {
+        if *value == 2 {
+            if let Some(mut subscription) = next_subscription.lock().unwrap().take() {
+                subscription.unsubscribe();
+                tx.send(()).unwrap();
+            }
+        }
+    });
+    subscription.lock().unwrap().replace(created);
+
+    assert!(rx.recv_timeout(Duration::from_secs(1)).is_ok());
 }
\ No newline at end of file
@@ -0,0 +1,10 @@
+use crate::operators::just;
+use crate::tests::utils::{is_completed, values_sent};
+
+#[test]
+fn create() {
+    let obs = just(String::from("value"));
+
+    assert!(values_sent(&obs, &[String::from("value")]));
+    assert!(is_completed(&obs));
+}
@@ -45,7 +45,6 @@ fn map_data() {
     struct DataItem {
         value: i32
     }
-    ;
 
     let data = [
         DataItem { value: 1 },
@@ -104,4 +103,28 @@ fn successive_operators() {
 
     assert!(values_sent(&result, &[4, 6]));
     assert!(is_completed(&result));
-}
\ No newline at end of file
+}
+
+#[test]
+fn map_captured_state() {
+    let factor = 3;
+    let obs = of(&[1, 2, 3])
+        .map(move |item| item * factor);
+
+    assert!(values_sent(&obs, &[3, 6, 9]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn map_mutable_state() {
+    let mut count = 0;
+    let obs = of(&["a", "b", "c"])
+        .map(move |item| {
+            count += 1;
+            format!("{}{}", item, count)
+        });
+
+    assert!(values_sent(&obs, &[String::from("a1"), String::from("b2"), String::from("c3")]));
+    // every subscription starts with a fresh copy of the closure
+    assert!(values_sent(&obs, &[String::from("a1"), String::from("b2"), String::from("c3")]));
+}
@@ -0,0 +1,62 @@
+use std::sync::mpsc::channel;
+
+use crate::error::RxError;
+use crate::observable::{Observable, ObservableLike, Unsubscriber};
+use crate::observer::ObserverLike;
+use crate::subject::Subject;
+use crate::subscriber::Subscriber;
+
+#[derive(Clone, Debug, PartialEq)]
+enum ServiceError {
+    NotFound(u32),
+    Other(String),
+}
+
+fn failing() -> Observable<'static, u32, ServiceError> {
+    Observable::new(|subscriber: Subscriber<'static, u32, ServiceError>| {
+        subscriber.next(&1);
+        subscriber.error(&ServiceError::NotFound(42));
+
+        Unsubscriber::new(|| {})
+    })
+}
+
+#[test]
+fn custom_error() {
+    let (tx, rx) = channel();
+    failing()
+        .map(|value| value * 2)
+        .subscribe_error(move |e| tx.send(e.clone()).unwrap());
+
+    assert_eq!(rx.try_recv(), Ok(ServiceError::NotFound(42)));
+}
+
+#[test]
+fn map_err_convert() {
+    let (tx, rx) = channel();
+    failing()
+        .map_err(|e: &ServiceError| RxError::CustomError(format!("{:?}", e)))
+        .subscribe_error(move |e| tx.send(e.to_string()).unwrap());
+
+    assert_eq!(rx.try_recv(), Ok(String::from("NotFound(42)")));
+}
+
+#[test]
+fn map_err_keeps_values() {
+    let (tx, rx) = channel();
+    failing()
+        .map_err(|_| ServiceError::Other(String::from("mapped")))
+        .subscribe_next(move |value| tx.send(*value).unwrap());
+
+    assert_eq!(rx.try_iter().collect::<Vec<u32>>(), vec![1]);
+}
+
+#[test]
+fn subject_custom_error() {
+    let (tx, rx) = channel();
+    let subject: Subject<u32, ServiceError> = Subject::default();
+    subject.subscribe_error(move |e| tx.send(e.clone()).unwrap());
+
+    failing().subscribe(subject);
+    assert_eq!(rx.try_recv(), Ok(ServiceError::NotFound(42)));
+}
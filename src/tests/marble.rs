@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use crate::error::RxError;
+use crate::operators::{empty, interval, throw_error};
+use crate::testing::MarbleTester;
+
+#[test]
+fn cold_map_filter() {
+    let tester = MarbleTester::new();
+    let source = tester.cold("-a-b-c-|", &[('a', 1), ('b', 2), ('c', 3)]);
+
+    let result = source.observable()
+        .map(|value| value * 10)
+        .filter(|value| value > &10);
+
+    tester.expect_observable(result).to_be("---b-c-|", &[('b', 20), ('c', 30)]);
+    tester.expect_subscriptions(source.subscriptions()).to_be("^------!");
+    tester.flush();
+}
+
+#[test]
+fn cold_delay() {
+    let tester = MarbleTester::new();
+    let source = tester.cold("-a-(bc)-|", &[('a', 1), ('b', 2), ('c', 3)]);
+
+    let result = source.observable().delay(Duration::from_millis(2), tester.scheduler());
+
+    tester.expect_observable(result).to_be("---a-(bc)-|", &[('a', 1), ('b', 2), ('c', 3)]);
+    tester.flush();
+}
+
+#[test]
+fn cold_error() {
+    let tester = MarbleTester::new();
+    let source = tester.cold("-a-#", &[('a', 1)]);
+
+    tester.expect_observable(source.observable()).to_be("-a-#", &[('a', 1)]);
+    tester.flush();
+}
+
+#[test]
+fn cold_unsubscribe() {
+    let tester = MarbleTester::new();
+    let source = tester.cold("-a-b-c-|", &[('a', 1), ('b', 2), ('c', 3)]);
+
+    tester.expect_observable_with(source.observable(), "--^--!").to_be("---a-", &[('a', 1)]);
+    tester.expect_subscriptions(source.subscriptions()).to_be("--^--!");
+    tester.flush();
+}
+
+#[test]
+fn hot_late_subscription() {
+    let tester = MarbleTester::new();
+    let source = tester.hot("-a-^-b-c-|", &[('a', 1), ('b', 2), ('c', 3)]);
+
+    tester.expect_observable_with(source.observable(), "---^").to_be("----c-|", &[('c', 3)]);
+    tester.expect_subscriptions(source.subscriptions()).to_be("---^--!");
+    tester.flush();
+}
+
+#[test]
+fn hot_multiple_subscriptions() {
+    let tester = MarbleTester::new();
+    let source = tester.hot("^-a-b-|", &[('a', 1), ('b', 2)]);
+
+    tester.expect_observable_with(source.observable(), "^--!").to_be("--a", &[('a', 1)]);
+    tester.expect_observable_with(source.observable(), "---^").to_be("----b-|", &[('b', 2)]);
+    tester.expect_subscriptions(source.subscriptions()).to_be_all(&["^--!", "---^--!"]);
+    tester.flush();
+}
+
+#[test]
+fn interval_marbles() {
+    let tester = MarbleTester::new();
+    let obs = interval(Duration::from_millis(2), tester.scheduler());
+
+    tester.expect_observable_with(obs, "^------!").to_be("--a-b-c", &[('a', 0), ('b', 1), ('c', 2)]);
+    tester.flush();
+}
+
+#[test]
+#[should_panic(expected = "marble expectations failed")]
+fn unexpected_events() {
+    let tester = MarbleTester::new();
+    let source = tester.cold("-a-|", &[('a', 1)]);
+
+    tester.expect_observable(source.observable()).to_be("--a-|", &[('a', 1)]);
+    tester.flush();
+}
+
+#[test]
+fn cold_repeat() {
+    let tester = MarbleTester::new();
+    let source = tester.cold("-a-|", &[('a', 1)]);
+
+    tester.expect_observable(source.observable().repeat(3)).to_be("-a--a--a-|", &[('a', 1)]);
+    tester.expect_subscriptions(source.subscriptions()).to_be_all(&["^--!", "---^--!", "------^--!"]);
+    tester.flush();
+}
+
+#[test]
+fn cold_repeat_when() {
+    let tester = MarbleTester::new();
+    let source = tester.cold("-a|", &[('a', 1)]);
+    let notifier = tester.cold("--x|", &[('x', ())]);
+
+    let result = source.observable()
+        .repeat_when(move |count| if count < 2 { notifier.observable() } else { empty() });
+
+    tester.expect_observable(result).to_be("-a---a|", &[('a', 1)]);
+    tester.flush();
+}
+
+#[test]
+fn expect_error_message() {
+    let tester = MarbleTester::new();
+
+    tester.expect_observable(throw_error::<i32, _>(RxError::Timeout)).to_be_with_error("#", &[], "timeout");
+    tester.flush();
+}
+
+#[test]
+fn cold_map_err() {
+    let tester = MarbleTester::new();
+    let source = tester.cold_with_error("-a-#", &[('a', 1)], "not found");
+
+    let result = source.observable().map_err(|e| format!("request failed: {}", e));
+
+    tester.expect_observable(result).to_be_with_error("-a-#", &[('a', 1)], "request failed: not found");
+    tester.flush();
+}
+
+#[test]
+#[should_panic(expected = "unsubscription point before the subscription point")]
+fn unsubscription_before_subscription() {
+    let tester = MarbleTester::new();
+    let source = tester.cold("-a-|", &[('a', 1)]);
+
+    tester.expect_subscriptions(source.subscriptions()).to_be("!-^");
+}
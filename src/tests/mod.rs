@@ -1,8 +1,26 @@
+mod defer;
 mod delay;
+mod empty;
+mod error;
 mod filter;
+mod from_iter;
+mod generate;
 mod interval;
+mod just;
 mod map;
-mod of;
+mod map_err;
+mod marble;
+mod never;
+mod observable;
 mod observer;
+mod of;
+mod range;
+mod reduce;
+mod repeat;
+mod scan;
+mod scheduler;
+mod subject;
 mod subscription;
+mod throw_error;
+mod timer;
 mod utils;
\ No newline at end of file
@@ -0,0 +1,30 @@
+use std::sync::mpsc::channel;
+
+use crate::operators::{never, never_with_error};
+
+#[test]
+fn create() {
+    let obs = never::<i32>();
+
+    let (tx, rx) = channel();
+    let (error_tx, complete_tx) = (tx.clone(), tx.clone());
+    let subscription = obs.subscribe_all(
+        move |_| tx.send("next").unwrap(),
+        move |_| error_tx.send("error").unwrap(),
+        move || complete_tx.send("complete").unwrap(),
+    );
+
+    assert!(rx.try_recv().is_err());
+    assert!(!subscription.is_closed());
+}
+
+#[test]
+fn create_with_error() {
+    let obs = never_with_error::<i32, String>();
+
+    let (tx, rx) = channel();
+    let subscription = obs.subscribe_error(move |e| tx.send(e.clone()).unwrap());
+
+    assert!(rx.try_recv().is_err());
+    assert!(!subscription.is_closed());
+}
\ No newline at end of file
@@ -0,0 +1,69 @@
+use std::sync::mpsc::{channel, Sender};
+
+use crate::error::RxError;
+use crate::observable::{Observable, ObservableLike};
+use crate::observer::ObserverLike;
+use crate::operators::of;
+use crate::subject::Subject;
+use crate::tests::utils::{is_completed, values_sent};
+
+struct CollectObserver {
+    sender: Sender<i32>,
+}
+
+impl ObserverLike for CollectObserver {
+    type Value = i32;
+    type Error = RxError;
+
+    fn next(&self, value: &Self::Value) {
+        self.sender.send(*value).unwrap();
+    }
+
+    fn error(&self, _e: &Self::Error) {}
+
+    fn complete(&mut self) {}
+}
+
+fn doubled(values: &[i32]) -> Observable<'_, i32> {
+    of(values).map(|item| item * 2)
+}
+
+#[test]
+fn return_from_function() {
+    let data = [1, 2, 3];
+    let obs = doubled(&data).filter(|item| item > &2);
+
+    assert!(values_sent(&obs, &[4, 6]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn store_in_struct() {
+    struct Source<'a> {
+        values: Observable<'a, i32>,
+    }
+
+    let data = [1, 2, 3];
+    let source = Source { values: of(&data) };
+
+    assert!(values_sent(&source.values, &data));
+    assert!(is_completed(&source.values));
+}
+
+#[test]
+fn different_observers() {
+    let data = [1, 2, 3];
+    let obs = of(&data).map(|item| item + 1);
+
+    let (tx, rx) = channel();
+    obs.subscribe(CollectObserver { sender: tx });
+    assert_eq!(rx.try_iter().collect::<Vec<i32>>(), vec![2, 3, 4]);
+
+    assert!(values_sent(&obs, &[2, 3, 4]));
+
+    let (tx, rx) = channel();
+    let subject = Subject::new();
+    subject.subscribe_next(move |value| tx.send(*value).unwrap());
+    obs.subscribe(subject);
+    assert_eq!(rx.try_iter().collect::<Vec<i32>>(), vec![2, 3, 4]);
+}
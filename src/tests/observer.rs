@@ -1,5 +1,11 @@
+use std::sync::mpsc::{channel, Sender};
+
 use crate::error::RxError;
+use crate::observable::{Observable, ObservableLike, Unsubscriber};
 use crate::observer::{Observer, ObserverLike};
+use crate::subject::Subject;
+use crate::subscriber::Subscriber;
+use crate::subscription::Unsubscribable;
 
 #[test]
 fn new() {
@@ -8,7 +14,7 @@ fn new() {
 		|err| println!("{}", err),
 		|| println!("complete"),
 	);
-    assert_eq!(observer.stopped, false);
+    assert!(!observer.stopped);
 }
 
 #[test]
@@ -42,5 +48,95 @@ fn complete() {
 	);
 
     observer.complete();
-    assert_eq!(observer.stopped, true);
-}
\ No newline at end of file
+    assert!(observer.stopped);
+}
+
+struct CollectObserver {
+    sender: Sender<String>,
+}
+
+impl ObserverLike for CollectObserver {
+    type Value = u32;
+    type Error = RxError;
+
+    fn next(&self, value: &Self::Value) {
+        self.sender.send(value.to_string()).unwrap();
+    }
+
+    fn error(&self, e: &Self::Error) {
+        self.sender.send(e.to_string()).unwrap();
+    }
+
+    fn complete(&mut self) {
+        self.sender.send(String::from("complete")).unwrap();
+    }
+}
+
+#[test]
+fn safe_after_error() {
+    let obs = Observable::new(|mut subscriber: Subscriber<u32>| {
+        subscriber.next(&1);
+        subscriber.error(&RxError::Timeout);
+        subscriber.next(&2);
+        subscriber.error(&RxError::Timeout);
+        subscriber.complete();
+
+        Unsubscriber::new(|| {})
+    });
+
+    let (tx, rx) = channel();
+    let subscription = obs.subscribe(CollectObserver { sender: tx });
+
+    assert_eq!(rx.try_iter().collect::<Vec<String>>(), vec!["1", "timeout"]);
+    assert!(subscription.is_closed());
+}
+
+#[test]
+fn safe_teardown_on_complete() {
+    let (teardown_tx, teardown_rx) = channel();
+    let subject = Subject::new();
+
+    let source = subject.clone();
+    let obs = Observable::new(move |subscriber: Subscriber<u32>| {
+        let mut subscription = source.subscribe(subscriber);
+        let teardown_tx = teardown_tx.clone();
+
+        Unsubscriber::new(move || {
+            subscription.unsubscribe();
+            teardown_tx.send(()).unwrap();
+        })
+    });
+
+    let (tx, rx) = channel();
+    let subscription = obs.subscribe(CollectObserver { sender: tx });
+
+    let mut source = subject.clone();
+    source.next(&1);
+    assert!(teardown_rx.try_recv().is_err());
+
+    source.complete();
+    assert_eq!(rx.try_iter().collect::<Vec<String>>(), vec!["1", "complete"]);
+    assert_eq!(teardown_rx.try_iter().count(), 1);
+    assert!(subscription.is_closed());
+}
+
+#[test]
+fn safe_after_unsubscribe() {
+    let subject = Subject::new();
+
+    let source = subject.clone();
+    let obs = Observable::new(move |subscriber: Subscriber<u32>| {
+        // the source keeps the subscriber after the unsubscription
+        source.subscribe(subscriber);
+        Unsubscriber::new(|| {})
+    });
+
+    let (tx, rx) = channel();
+    let mut subscription = obs.subscribe(CollectObserver { sender: tx });
+
+    subject.next(&1);
+    subscription.unsubscribe();
+    subject.next(&2);
+
+    assert_eq!(rx.try_iter().collect::<Vec<String>>(), vec!["1"]);
+}
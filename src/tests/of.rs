@@ -1,5 +1,8 @@
+use std::sync::{Arc, Mutex};
+
+use crate::observable::ObservableLike;
 use crate::operators::of;
-use crate::tests::utils::{is_completed, values_sent};
+use crate::tests::utils::{is_completed, values_sent, TakeObserver};
 
 #[test]
 fn create_i32() {
@@ -14,7 +17,6 @@ fn create_data() {
     struct DataItem {
         value: i32
     }
-    ;
 
     let data = [
         DataItem { value: 1 },
@@ -25,4 +27,21 @@ fn create_data() {
     let obs = of(&data);
     assert!(values_sent(&obs, &data));
     assert!(is_completed(&obs));
-}
\ No newline at end of file
+}
+
+#[test]
+fn stop_when_closed() {
+    let data: Vec<i32> = (0..10_000).collect();
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    of(&data).subscribe(TakeObserver { count: 3, received: received.clone() });
+    assert_eq!(received.lock().unwrap().len(), 3);
+
+    // the check goes through the operators to the final observer
+    received.lock().unwrap().clear();
+    of(&data)
+        .map(|value| value * 2)
+        .filter(|value| value % 4 == 0)
+        .subscribe(TakeObserver { count: 2, received: received.clone() });
+    assert_eq!(received.lock().unwrap().len(), 2);
+}
@@ -0,0 +1,37 @@
+use std::sync::{Arc, Mutex};
+
+use crate::observable::ObservableLike;
+use crate::operators::range;
+use crate::tests::utils::{is_completed, values_sent, TakeObserver};
+
+#[test]
+fn create() {
+    let obs = range(-1, 4);
+
+    assert!(values_sent(&obs, &[-1, 0, 1, 2]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn empty() {
+    let obs = range(5, 0);
+
+    assert!(values_sent(&obs, &[]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn stop_at_max() {
+    let obs = range(i64::MAX - 1, 3);
+
+    assert!(values_sent(&obs, &[i64::MAX - 1, i64::MAX]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn max_count() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    range(0, u64::MAX).subscribe(TakeObserver { count: 3, received: received.clone() });
+    assert_eq!(*received.lock().unwrap(), vec![0, 1, 2]);
+}
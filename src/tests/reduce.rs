@@ -0,0 +1,50 @@
+use std::sync::mpsc::channel;
+
+use crate::error::RxError;
+use crate::operators::{empty, of, throw_error};
+use crate::tests::utils::{is_completed, values_sent};
+
+#[test]
+fn reduce_sum() {
+    let obs = of(&[1, 2, 3])
+        .reduce(0, |total, value| total + value);
+
+    assert!(values_sent(&obs, &[6]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn reduce_emit_once() {
+    let obs = of(&[1, 2, 3])
+        .reduce(0, |total, value| total + value);
+
+    let (tx, rx) = channel();
+    obs.subscribe_next(move |total| tx.send(*total).unwrap());
+
+    assert_eq!(rx.try_iter().collect::<Vec<i32>>(), vec![6]);
+}
+
+#[test]
+fn reduce_empty() {
+    let obs = empty::<i32>()
+        .reduce(10, |total, value| total + value);
+
+    assert!(values_sent(&obs, &[10]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn reduce_error() {
+    let obs = throw_error::<i32, _>(RxError::Timeout)
+        .reduce(0, |total, value| total + value);
+
+    let (tx, rx) = channel();
+    let (next_tx, error_tx) = (tx.clone(), tx.clone());
+    obs.subscribe_all(
+        move |total| next_tx.send(total.to_string()).unwrap(),
+        move |e| error_tx.send(e.to_string()).unwrap(),
+        || {},
+    );
+
+    assert_eq!(rx.try_iter().collect::<Vec<String>>(), vec!["timeout"]);
+}
@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::channel;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::RxError;
+use crate::operators::{empty, empty_with_error, from_vec, just, of, throw_error, timer_with_error};
+use crate::scheduler::ImmediateScheduler;
+use crate::tests::utils::{is_completed, values_sent};
+
+#[test]
+fn repeat() {
+    let obs = of(&[1, 2]).repeat(3);
+
+    assert!(values_sent(&obs, &[1, 2, 1, 2, 1, 2]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn repeat_zero() {
+    let obs = of(&[1, 2]).repeat(0);
+
+    let (tx, rx) = channel();
+    obs.subscribe_next(move |value| tx.send(*value).unwrap());
+
+    assert!(rx.try_recv().is_err());
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn repeat_many() {
+    let obs = just(1).repeat(100_000);
+
+    let (tx, rx) = channel();
+    obs.subscribe_next(move |value| tx.send(*value).unwrap());
+
+    assert_eq!(rx.try_iter().count(), 100_000);
+}
+
+#[test]
+fn repeat_error() {
+    let obs = throw_error::<i32, _>(RxError::Timeout).repeat(3);
+
+    let (tx, rx) = channel();
+    obs.subscribe_error(move |e| tx.send(e.to_string()).unwrap());
+
+    assert_eq!(rx.try_iter().collect::<Vec<String>>(), vec!["timeout"]);
+    assert!(!is_completed(&obs));
+}
+
+#[test]
+fn repeat_when() {
+    let notified = Arc::new(AtomicU64::new(0));
+
+    let completions = notified.clone();
+    let obs = from_vec(vec![1, 2]).repeat_when(move |count| {
+        completions.store(count, Ordering::SeqCst);
+        if count < 2 { just(()) } else { empty() }
+    });
+
+    assert!(values_sent(&obs, &[1, 2, 1, 2]));
+    assert_eq!(notified.load(Ordering::SeqCst), 2);
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn repeat_when_error() {
+    let obs = of(&[1]).repeat_when(|_| throw_error::<(), _>(RxError::Timeout));
+
+    let (tx, rx) = channel();
+    obs.subscribe_error(move |e| tx.send(e.to_string()).unwrap());
+
+    assert_eq!(rx.try_iter().collect::<Vec<String>>(), vec!["timeout"]);
+}
+
+#[test]
+fn repeat_when_custom_error() {
+    let obs = of(&[1])
+        .map_err(|e| e.to_string())
+        .repeat_when(|count| match count {
+            1 => timer_with_error(Duration::from_millis(0), ImmediateScheduler::new()),
+            _ => empty_with_error(),
+        });
+
+    let (tx, rx) = channel();
+    let complete_tx = tx.clone();
+    obs.subscribe_all(
+        move |value| tx.send(Some(*value)).unwrap(),
+        |_: &String| {},
+        move || complete_tx.send(None).unwrap(),
+    );
+
+    assert_eq!(rx.try_iter().collect::<Vec<Option<i32>>>(), vec![Some(1), Some(1), None]);
+}
\ No newline at end of file
@@ -0,0 +1,69 @@
+use std::sync::mpsc::channel;
+
+use crate::error::RxError;
+use crate::observable::{Observable, Unsubscriber};
+use crate::observer::ObserverLike;
+use crate::operators::{empty, of};
+use crate::subscriber::Subscriber;
+use crate::tests::utils::{is_completed, values_sent};
+
+#[test]
+fn scan_sum() {
+    let obs = of(&[1, 2, 3])
+        .scan(0, |total, value| total + value);
+
+    assert!(values_sent(&obs, &[1, 3, 6]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn scan_to_vec() {
+    let obs = of(&["a", "b"])
+        .scan(Vec::new(), |items, item| {
+            let mut items = items.clone();
+            items.push(item.to_string());
+            items
+        });
+
+    assert!(values_sent(&obs, &[vec![String::from("a")], vec![String::from("a"), String::from("b")]]));
+}
+
+#[test]
+fn scan_seed_per_subscription() {
+    let obs = of(&[1, 2])
+        .scan(10, |total, value| total + value);
+
+    assert!(values_sent(&obs, &[11, 13]));
+    assert!(values_sent(&obs, &[11, 13]));
+}
+
+#[test]
+fn scan_empty() {
+    let obs = empty::<i32>()
+        .scan(0, |total, value| total + value);
+
+    assert!(values_sent(&obs, &[]));
+    assert!(is_completed(&obs));
+}
+
+#[test]
+fn scan_error() {
+    let obs = Observable::new(|subscriber: Subscriber<i32>| {
+        subscriber.next(&1);
+        subscriber.next(&2);
+        subscriber.error(&RxError::Timeout);
+
+        Unsubscriber::new(|| {})
+    });
+
+    let (tx, rx) = channel();
+    let (next_tx, error_tx) = (tx.clone(), tx.clone());
+    obs.scan(0, |total, value| total + value)
+        .subscribe_all(
+            move |total| next_tx.send(total.to_string()).unwrap(),
+            move |e| error_tx.send(e.to_string()).unwrap(),
+            || {},
+        );
+
+    assert_eq!(rx.try_iter().collect::<Vec<String>>(), vec!["1", "3", "timeout"]);
+}
@@ -0,0 +1,253 @@
+use std::sync::mpsc::{channel, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::operators::interval;
+use crate::scheduler::{CurrentThreadScheduler, ImmediateScheduler, NewThreadScheduler, Scheduler, TestScheduler, ThreadPoolScheduler};
+use crate::subscription::Unsubscribable;
+
+#[test]
+fn immediate_schedule() {
+    let executed = Arc::new(Mutex::new(false));
+    let task_executed = executed.clone();
+
+    ImmediateScheduler::new().schedule(Box::new(move || *task_executed.lock().unwrap() = true));
+    assert!(*executed.lock().unwrap());
+}
+
+#[test]
+fn immediate_schedule_periodic() {
+    let mut count = 0;
+    let (tx, rx) = channel();
+
+    let handle = ImmediateScheduler::new().schedule_periodic(
+        Duration::from_millis(0),
+        Duration::from_millis(1),
+        Box::new(move |handle| {
+            count += 1;
+            tx.send(count).unwrap();
+            if count == 3 {
+                handle.cancel();
+            }
+        }),
+    );
+
+    assert!(handle.is_cancelled());
+    assert_eq!(rx.try_iter().collect::<Vec<i32>>(), vec![1, 2, 3]);
+}
+
+#[test]
+fn current_thread_trampoline() {
+    let scheduler = CurrentThreadScheduler::new();
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let outer_events = events.clone();
+    scheduler.schedule(Box::new(move || {
+        outer_events.lock().unwrap().push("outer start");
+
+        let inner_events = outer_events.clone();
+        scheduler.schedule(Box::new(move || inner_events.lock().unwrap().push("inner")));
+
+        outer_events.lock().unwrap().push("outer end");
+    }));
+
+    assert_eq!(*events.lock().unwrap(), vec!["outer start", "outer end", "inner"]);
+    assert!(!CurrentThreadScheduler::is_scheduling());
+}
+
+#[test]
+fn current_thread_due_order() {
+    let scheduler = CurrentThreadScheduler::new();
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let outer_events = events.clone();
+    scheduler.schedule(Box::new(move || {
+        let late_events = outer_events.clone();
+        scheduler.schedule_after(Duration::from_millis(10), Box::new(move || late_events.lock().unwrap().push(2)));
+
+        let early_events = outer_events.clone();
+        scheduler.schedule_after(Duration::from_millis(5), Box::new(move || early_events.lock().unwrap().push(1)));
+    }));
+
+    assert_eq!(*events.lock().unwrap(), vec![1, 2]);
+}
+
+#[test]
+fn new_thread_schedule() {
+    let (tx, rx) = channel();
+
+    NewThreadScheduler::new().schedule(Box::new(move || tx.send(thread::current().id()).unwrap()));
+
+    let task_thread = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+    assert_ne!(task_thread, thread::current().id());
+}
+
+#[test]
+fn new_thread_cancel() {
+    let (tx, rx) = channel();
+
+    let handle = NewThreadScheduler::new().schedule_after(
+        Duration::from_millis(20),
+        Box::new(move || tx.send(()).unwrap()),
+    );
+    handle.cancel();
+
+    assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+}
+
+#[test]
+fn new_thread_join() {
+    let (tx, rx) = channel();
+
+    let handle = NewThreadScheduler::new().schedule_after(
+        Duration::from_millis(10),
+        Box::new(move || tx.send(()).unwrap()),
+    );
+    handle.join();
+
+    assert!(rx.try_recv().is_ok());
+}
+
+#[test]
+fn new_thread_join_on_cancel() {
+    let (tx, rx) = channel();
+
+    let handle = NewThreadScheduler::join_on_cancel().schedule_periodic(
+        Duration::from_millis(0),
+        Duration::from_millis(1),
+        Box::new(move |_| tx.send(()).unwrap()),
+    );
+    rx.recv_timeout(Duration::from_secs(1)).unwrap();
+    handle.cancel();
+
+    // the thread finished, and dropped its sender
+    rx.try_iter().count();
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn new_thread_cancel_from_worker() {
+    let scheduler = NewThreadScheduler::join_on_cancel();
+    let lock = Arc::new(Mutex::new(()));
+    let (tx, rx) = channel();
+
+    let task_lock = lock.clone();
+    scheduler.schedule(Box::new(move || {
+        let _guard = task_lock.lock().unwrap();
+
+        // the other task is running, and waits for the lock held by this worker
+        let waiting_lock = task_lock.clone();
+        let waiting = scheduler.schedule(Box::new(move || drop(waiting_lock.lock().unwrap())));
+        thread::sleep(Duration::from_millis(10));
+
+        waiting.cancel();
+        tx.send(()).unwrap();
+    }));
+
+    assert!(rx.recv_timeout(Duration::from_secs(1)).is_ok());
+}
+
+#[test]
+fn thread_pool_due_order() {
+    let scheduler = ThreadPoolScheduler::new(1);
+    let (tx, rx) = channel();
+
+    let late = tx.clone();
+    scheduler.schedule_after(Duration::from_millis(20), Box::new(move || late.send(2).unwrap()));
+    scheduler.schedule_after(Duration::from_millis(10), Box::new(move || tx.send(1).unwrap()));
+
+    assert_eq!(rx.recv_timeout(Duration::from_secs(1)), Ok(1));
+    assert_eq!(rx.recv_timeout(Duration::from_secs(1)), Ok(2));
+}
+
+#[test]
+fn thread_pool_periodic() {
+    let scheduler = ThreadPoolScheduler::new(2);
+    let (tx, rx) = channel();
+
+    let mut count = 0;
+    scheduler.schedule_periodic(
+        Duration::from_millis(0),
+        Duration::from_millis(1),
+        Box::new(move |handle| {
+            count += 1;
+            tx.send(count).unwrap();
+            if count == 3 {
+                handle.cancel();
+            }
+        }),
+    );
+
+    let values: Vec<i32> = rx.iter().take(3).collect();
+    assert_eq!(values, vec![1, 2, 3]);
+    assert!(rx.recv_timeout(Duration::from_millis(20)).is_err());
+}
+
+#[test]
+fn interval_thread_pool() {
+    let (tx, rx) = channel();
+
+    let mut subscription = interval(Duration::from_millis(1), ThreadPoolScheduler::new(1))
+        .subscribe_next(move |value| {
+            let _ = tx.send(*value);
+        });
+
+    let values: Vec<u64> = rx.iter().take(3).collect();
+    subscription.unsubscribe();
+
+    assert_eq!(values, vec![0, 1, 2]);
+}
+
+#[test]
+fn test_scheduler_advance_by() {
+    let scheduler = TestScheduler::new();
+    let (tx, rx) = channel();
+
+    let late = tx.clone();
+    scheduler.schedule_after(Duration::from_millis(20), Box::new(move || late.send(2).unwrap()));
+    scheduler.schedule_after(Duration::from_millis(10), Box::new(move || tx.send(1).unwrap()));
+
+    scheduler.advance_by(Duration::from_millis(9));
+    assert_eq!(rx.try_recv().ok(), None);
+
+    scheduler.advance_by(Duration::from_millis(1));
+    assert_eq!(rx.try_recv().ok(), Some(1));
+    assert_eq!(scheduler.elapsed(), Duration::from_millis(10));
+
+    scheduler.advance_to(Duration::from_millis(25));
+    assert_eq!(rx.try_recv().ok(), Some(2));
+    assert_eq!(scheduler.elapsed(), Duration::from_millis(25));
+}
+
+#[test]
+fn test_scheduler_nested_schedule() {
+    let scheduler = TestScheduler::new();
+    let times = Arc::new(Mutex::new(Vec::new()));
+
+    let (task_scheduler, task_times) = (scheduler.clone(), times.clone());
+    scheduler.schedule_after(Duration::from_millis(5), Box::new(move || {
+        task_times.lock().unwrap().push(task_scheduler.elapsed());
+
+        let (inner_scheduler, inner_times) = (task_scheduler.clone(), task_times.clone());
+        task_scheduler.schedule_after(Duration::from_millis(5), Box::new(move || {
+            inner_times.lock().unwrap().push(inner_scheduler.elapsed());
+        }));
+    }));
+
+    scheduler.flush();
+    assert_eq!(*times.lock().unwrap(), vec![Duration::from_millis(5), Duration::from_millis(10)]);
+}
+
+#[test]
+fn test_scheduler_cancel() {
+    let scheduler = TestScheduler::new();
+    let (tx, rx) = channel();
+
+    let handle = scheduler.schedule_after(Duration::from_millis(10), Box::new(move || tx.send(()).unwrap()));
+    handle.cancel();
+
+    scheduler.flush();
+    assert!(rx.try_recv().is_err());
+    assert_eq!(scheduler.elapsed(), Duration::from_millis(0));
+}
@@ -0,0 +1,491 @@
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::error::RxError;
+use crate::observable::ObservableLike;
+use crate::observer::ObserverLike;
+use crate::operators::interval;
+use crate::scheduler::{NewThreadScheduler, TestScheduler};
+use crate::subject::{AsyncSubject, BehaviorSubject, ReplaySubject, Subject};
+use crate::subscription::{SubjectSubscription, Unsubscribable};
+
+fn assert_send_sync<S: Send + Sync>(_subject: &S) {}
+
+#[test]
+fn shared_between_threads() {
+    let subject = Subject::new();
+    assert_send_sync(&subject);
+
+    let (tx, rx) = channel();
+    subject.subscribe_next(move |value: &i32| tx.send(*value).unwrap());
+
+    let producers: Vec<_> = (0..4)
+        .map(|_| {
+            let producer = subject.clone();
+            thread::spawn(move || (0..100).for_each(|value| producer.next(&value)))
+        })
+        .collect();
+    producers.into_iter().for_each(|producer| producer.join().unwrap());
+
+    assert_eq!(rx.try_iter().count(), 400);
+}
+
+#[test]
+fn owned_subscription() {
+    let subject = Subject::new();
+
+    let (tx, rx) = channel();
+    let mut subscription = subject.subscribe_next(move |value: &u64| tx.send(*value).unwrap());
+
+    let source = interval(Duration::from_millis(1), NewThreadScheduler::new());
+    let mut source_subscription = source.subscribe(subject.clone());
+
+    assert_eq!(rx.recv().unwrap(), 0);
+    assert_eq!(rx.recv().unwrap(), 1);
+
+    thread::spawn(move || subscription.unsubscribe()).join().unwrap();
+    source_subscription.unsubscribe();
+    assert!(!subject.is_closed());
+}
+
+#[test]
+fn observer_count() {
+    let mut subject = Subject::new();
+    assert_eq!(subject.observer_count(), 0);
+
+    let mut subscriptions: Vec<_> = (0..100)
+        .map(|_| subject.subscribe_next(|_: &i32| {}))
+        .collect();
+    assert_eq!(subject.observer_count(), 100);
+
+    subscriptions.iter_mut().step_by(2).for_each(|subscription| subscription.unsubscribe());
+    assert_eq!(subject.observer_count(), 50);
+
+    // unsubscribing twice doesn't remove another observer
+    subscriptions[0].unsubscribe();
+    assert_eq!(subject.observer_count(), 50);
+
+    subject.complete();
+    assert_eq!(subject.observer_count(), 0);
+}
+
+#[test]
+fn subscribe_closed() {
+    let (tx, rx) = channel();
+    let replay = ReplaySubject::new();
+    replay.next(&1);
+
+    let mut subjects = (Subject::new(), BehaviorSubject::new(0), replay, AsyncSubject::new());
+    subjects.0.unsubscribe();
+    subjects.1.unsubscribe();
+    subjects.2.unsubscribe();
+    subjects.3.unsubscribe();
+
+    let subscriptions = [
+        subjects.0.subscribe_next({ let tx = tx.clone(); move |value: &i32| tx.send(*value).unwrap() }),
+        subjects.1.subscribe_next({ let tx = tx.clone(); move |value: &i32| tx.send(*value).unwrap() }),
+        subjects.2.subscribe_next({ let tx = tx.clone(); move |value: &i32| tx.send(*value).unwrap() }),
+        subjects.3.subscribe_next(move |value: &i32| tx.send(*value).unwrap()),
+    ];
+
+    assert!(subscriptions.iter().all(|subscription| subscription.closed));
+    assert_eq!(subjects.0.observer_count(), 0);
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn complete_stops() {
+    let mut subject = Subject::new();
+
+    let (tx, rx) = channel();
+    let complete_tx = tx.clone();
+    subject.subscribe_all(
+        move |value: &i32| tx.send(Some(*value)).unwrap(),
+        |_| {},
+        move || complete_tx.send(None).unwrap(),
+    );
+
+    subject.next(&1);
+    subject.complete();
+    subject.next(&2);
+    subject.complete();
+
+    assert!(subject.is_stopped());
+    assert!(!subject.has_error());
+    assert_eq!(rx.try_iter().collect::<Vec<Option<i32>>>(), vec![Some(1), None]);
+
+    let (late_tx, late_rx) = channel();
+    subject.subscribe_complete(move || late_tx.send(()).unwrap());
+    assert_eq!(late_rx.try_iter().count(), 1);
+}
+
+#[test]
+fn error_stops() {
+    let mut subject = Subject::new();
+
+    let (tx, rx) = channel();
+    let error_tx = tx.clone();
+    subject.subscribe_all(
+        move |value: &i32| tx.send(value.to_string()).unwrap(),
+        move |e| error_tx.send(e.to_string()).unwrap(),
+        || {},
+    );
+
+    subject.error(&RxError::Timeout);
+    subject.next(&1);
+    subject.complete();
+
+    assert!(subject.is_stopped());
+    assert!(subject.has_error());
+    assert_eq!(rx.try_iter().collect::<Vec<String>>(), vec!["timeout"]);
+
+    let (late_tx, late_rx) = channel();
+    subject.subscribe_error(move |e| late_tx.send(e.to_string()).unwrap());
+    assert_eq!(late_rx.try_iter().collect::<Vec<String>>(), vec!["timeout"]);
+}
+
+#[test]
+fn reentrant_next() {
+    let subject = Subject::new();
+
+    let (tx, rx) = channel();
+    let feedback = subject.clone();
+    let feedback_tx = tx.clone();
+    subject.subscribe_next(move |value: &i32| {
+        feedback_tx.send(format!("first {}", value)).unwrap();
+        if *value == 1 {
+            feedback.next(&2);
+        }
+    });
+    subject.subscribe_next(move |value| tx.send(format!("second {}", value)).unwrap());
+
+    subject.next(&1);
+
+    assert_eq!(
+        rx.try_iter().collect::<Vec<String>>(),
+        vec!["first 1", "second 1", "first 2", "second 2"]
+    );
+}
+
+#[test]
+fn reentrant_subscribe() {
+    let subject = Subject::new();
+
+    let (tx, rx) = channel();
+    let inner = subject.clone();
+    subject.subscribe_next(move |value: &i32| {
+        if *value == 1 {
+            let tx = tx.clone();
+            inner.subscribe_next(move |value| tx.send(*value).unwrap());
+        }
+    });
+
+    subject.next(&1);
+    subject.next(&2);
+
+    assert_eq!(rx.try_iter().collect::<Vec<i32>>(), vec![2]);
+}
+
+#[test]
+fn reentrant_unsubscribe() {
+    let subject = Subject::new();
+
+    let (tx, rx) = channel();
+    let subscription: Arc<Mutex<Option<SubjectSubscription<i32>>>> = Arc::new(Mutex::new(None));
+    let own_subscription = subscription.clone();
+    *subscription.lock().unwrap() = Some(subject.subscribe_next(move |value: &i32| {
+        tx.send(*value).unwrap();
+        if let Some(subscription) = own_subscription.lock().unwrap().as_mut() {
+            subscription.unsubscribe();
+        }
+    }));
+
+    subject.next(&1);
+    subject.next(&2);
+
+    assert_eq!(rx.try_iter().collect::<Vec<i32>>(), vec![1]);
+}
+
+#[test]
+fn reentrant_behavior() {
+    let subject = BehaviorSubject::new(0);
+
+    let (tx, rx) = channel();
+    let feedback = subject.clone();
+    subject.subscribe_next(move |value: &i32| {
+        tx.send(*value).unwrap();
+        if *value < 3 {
+            feedback.next(&(value + 1));
+        }
+    });
+
+    assert_eq!(rx.try_iter().collect::<Vec<i32>>(), vec![0, 1, 2, 3]);
+    assert_eq!(subject.value(), 3);
+}
+
+#[test]
+fn behavior_current_value() {
+    let subject = BehaviorSubject::new(0);
+    assert_eq!(subject.value(), 0);
+
+    subject.next(&1);
+    subject.next(&2);
+    assert_eq!(subject.value(), 2);
+    assert!(subject.get_value().is_ok());
+}
+
+#[test]
+fn behavior_closed() {
+    let mut subject = BehaviorSubject::new(0);
+    subject.next(&1);
+    assert!(!ObserverLike::is_closed(&subject));
+
+    subject.unsubscribe();
+    subject.next(&2);
+    assert!(ObserverLike::is_closed(&subject));
+    assert_eq!(subject.value(), 1);
+
+    let mut completed = BehaviorSubject::new(0);
+    completed.complete();
+    assert!(ObserverLike::is_closed(&completed));
+}
+
+#[test]
+fn behavior_replay_latest() {
+    let subject = BehaviorSubject::new(0);
+
+    let (first_tx, first_rx) = channel();
+    subject.subscribe_next(move |value| first_tx.send(*value).unwrap());
+    subject.next(&1);
+
+    let (second_tx, second_rx) = channel();
+    let mut second = subject.subscribe_next(move |value| second_tx.send(*value).unwrap());
+    subject.next(&2);
+    second.unsubscribe();
+    subject.next(&3);
+
+    assert_eq!(first_rx.try_iter().collect::<Vec<i32>>(), vec![0, 1, 2, 3]);
+    assert_eq!(second_rx.try_iter().collect::<Vec<i32>>(), vec![1, 2]);
+}
+
+#[test]
+fn behavior_late_complete() {
+    let mut subject = BehaviorSubject::new(0);
+    subject.next(&1);
+    subject.complete();
+    subject.next(&2);
+
+    let (tx, rx) = channel();
+    let complete_tx = tx.clone();
+    subject.subscribe_all(
+        move |value| tx.send(Some(*value)).unwrap(),
+        |_| {},
+        move || complete_tx.send(None).unwrap(),
+    );
+
+    assert_eq!(rx.try_iter().collect::<Vec<Option<i32>>>(), vec![None]);
+    assert_eq!(subject.value(), 1);
+}
+
+#[test]
+fn behavior_late_error() {
+    let subject = BehaviorSubject::new(0);
+    subject.error(&RxError::Timeout);
+
+    let (tx, rx) = channel();
+    subject.subscribe_error(move |e| tx.send(e.to_string()).unwrap());
+
+    assert_eq!(rx.try_iter().collect::<Vec<String>>(), vec!["timeout"]);
+    assert!(matches!(subject.get_value(), Err(RxError::Timeout)));
+    assert_eq!(subject.value(), 0);
+}
+
+#[test]
+fn replay_all() {
+    let subject = ReplaySubject::new();
+    subject.next(&1);
+    subject.next(&2);
+
+    let (tx, rx) = channel();
+    subject.subscribe_next(move |value| tx.send(*value).unwrap());
+    subject.next(&3);
+
+    assert_eq!(rx.try_iter().collect::<Vec<i32>>(), vec![1, 2, 3]);
+}
+
+#[test]
+fn replay_closed() {
+    let mut subject = ReplaySubject::new();
+    subject.next(&1);
+    assert!(!ObserverLike::is_closed(&subject));
+
+    subject.unsubscribe();
+    subject.next(&2);
+    assert!(ObserverLike::is_closed(&subject));
+
+    let mut completed: ReplaySubject<i32> = ReplaySubject::new();
+    completed.complete();
+    assert!(ObserverLike::is_closed(&completed));
+}
+
+#[test]
+fn replay_buffer_size() {
+    let subject = ReplaySubject::<i32>::with_buffer_size(2);
+    subject.next(&1);
+    subject.next(&2);
+    subject.next(&3);
+
+    let (tx, rx) = channel();
+    subject.subscribe_next(move |value| tx.send(*value).unwrap());
+
+    assert_eq!(rx.try_iter().collect::<Vec<i32>>(), vec![2, 3]);
+}
+
+#[test]
+fn replay_window() {
+    let scheduler = TestScheduler::new();
+    let subject = ReplaySubject::<i32>::with_window(Duration::from_millis(10), scheduler.clone());
+
+    subject.next(&1);
+    scheduler.advance_by(Duration::from_millis(5));
+    subject.next(&2);
+    scheduler.advance_by(Duration::from_millis(5));
+    subject.next(&3);
+
+    let (tx, rx) = channel();
+    subject.subscribe_next(move |value| tx.send(*value).unwrap());
+    assert_eq!(rx.try_iter().collect::<Vec<i32>>(), vec![2, 3]);
+
+    scheduler.advance_by(Duration::from_millis(6));
+    let (tx, rx) = channel();
+    subject.subscribe_next(move |value| tx.send(*value).unwrap());
+    assert_eq!(rx.try_iter().collect::<Vec<i32>>(), vec![3]);
+}
+
+#[test]
+fn replay_buffer_size_and_window() {
+    let scheduler = TestScheduler::new();
+    let subject = ReplaySubject::<i32>::with_buffer_size_and_window(1, Duration::from_millis(10), scheduler.clone());
+    subject.next(&1);
+    subject.next(&2);
+
+    let (tx, rx) = channel();
+    subject.subscribe_next(move |value| tx.send(*value).unwrap());
+    assert_eq!(rx.try_iter().collect::<Vec<i32>>(), vec![2]);
+
+    scheduler.advance_by(Duration::from_millis(10));
+    let (tx, rx) = channel();
+    subject.subscribe_next(move |value| tx.send(*value).unwrap());
+    assert!(rx.try_iter().next().is_none());
+}
+
+#[test]
+fn replay_terminal() {
+    let mut subject = ReplaySubject::new();
+    subject.next(&1);
+    subject.complete();
+    subject.next(&2);
+
+    let (tx, rx) = channel();
+    let complete_tx = tx.clone();
+    subject.subscribe_all(
+        move |value| tx.send(Some(*value)).unwrap(),
+        |_| {},
+        move || complete_tx.send(None).unwrap(),
+    );
+
+    assert_eq!(rx.try_iter().collect::<Vec<Option<i32>>>(), vec![Some(1), None]);
+
+    let subject = ReplaySubject::new();
+    subject.next(&1);
+    subject.error(&RxError::Timeout);
+
+    let (tx, rx) = channel();
+    let error_tx = tx.clone();
+    subject.subscribe_all(
+        move |value| tx.send(value.to_string()).unwrap(),
+        move |e| error_tx.send(e.to_string()).unwrap(),
+        || {},
+    );
+
+    assert_eq!(rx.try_iter().collect::<Vec<String>>(), vec!["1", "timeout"]);
+}
+
+#[test]
+fn async_last_value() {
+    let mut subject = AsyncSubject::new();
+
+    let (first_tx, first_rx) = channel();
+    let first_complete_tx = first_tx.clone();
+    subject.subscribe_all(
+        move |value| first_tx.send(Some(*value)).unwrap(),
+        |_| {},
+        move || first_complete_tx.send(None).unwrap(),
+    );
+
+    subject.next(&1);
+    subject.next(&2);
+    assert!(first_rx.try_iter().next().is_none());
+
+    subject.complete();
+    subject.next(&3);
+    assert_eq!(first_rx.try_iter().collect::<Vec<Option<i32>>>(), vec![Some(2), None]);
+
+    let (second_tx, second_rx) = channel();
+    let second_complete_tx = second_tx.clone();
+    subject.subscribe_all(
+        move |value| second_tx.send(Some(*value)).unwrap(),
+        |_| {},
+        move || second_complete_tx.send(None).unwrap(),
+    );
+    assert_eq!(second_rx.try_iter().collect::<Vec<Option<i32>>>(), vec![Some(2), None]);
+}
+
+#[test]
+fn async_closed() {
+    let mut subject = AsyncSubject::new();
+    subject.next(&1);
+    assert!(!ObserverLike::is_closed(&subject));
+
+    subject.complete();
+    assert!(ObserverLike::is_closed(&subject));
+
+    let mut unsubscribed: AsyncSubject<i32> = AsyncSubject::new();
+    unsubscribed.unsubscribe();
+    assert!(ObserverLike::is_closed(&unsubscribed));
+}
+
+#[test]
+fn async_empty() {
+    let mut subject = AsyncSubject::<i32>::new();
+    subject.complete();
+
+    let (tx, rx) = channel();
+    let complete_tx = tx.clone();
+    subject.subscribe_all(
+        move |value| tx.send(Some(*value)).unwrap(),
+        |_| {},
+        move || complete_tx.send(None).unwrap(),
+    );
+
+    assert_eq!(rx.try_iter().collect::<Vec<Option<i32>>>(), vec![None]);
+}
+
+#[test]
+fn async_error() {
+    let subject = AsyncSubject::new();
+    subject.next(&1);
+    subject.error(&RxError::Timeout);
+
+    let (tx, rx) = channel();
+    let error_tx = tx.clone();
+    subject.subscribe_all(
+        move |value: &i32| tx.send(value.to_string()).unwrap(),
+        move |e| error_tx.send(e.to_string()).unwrap(),
+        || {},
+    );
+
+    assert_eq!(rx.try_iter().collect::<Vec<String>>(), vec!["timeout"]);
+}
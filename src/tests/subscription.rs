@@ -1,14 +1,20 @@
-use std::cell::RefCell;
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use crate::observable::Unsubscriber;
 use crate::observer::Observer;
-use crate::subscription::{SubjectSubscription, Subscription, Unsubscribable};
+use crate::operators::interval;
+use crate::scheduler::NewThreadScheduler;
+use crate::subject::Subject;
+use crate::subscriber::Subscriber;
+use crate::subscription::{DisposeBag, SubjectObserver, SubjectObservers, SubjectSubscription, Subscription, Unsubscribable};
 
 #[test]
 fn observable_new() {
     let unsubscriber = Unsubscriber::new(|| {});
     let subscription = Subscription::new(unsubscriber);
-    assert_eq!(subscription.closed, false);
+    assert!(!subscription.is_closed());
 }
 
 #[test]
@@ -17,67 +23,216 @@ fn observable_unsubscribe() {
     let mut subscription = Subscription::new(unsubscriber);
 
     subscription.unsubscribe();
-    assert_eq!(subscription.closed, true);
+    assert!(subscription.is_closed());
 }
 
 #[test]
-fn subject_new() {
+fn observable_teardown_order() {
+    let (tx, rx) = channel();
+
+    let unsubscriber_tx = tx.clone();
+    let mut parent = Subscription::new(Unsubscriber::new(move || unsubscriber_tx.send("unsubscriber").unwrap()));
+
+    let child_tx = tx.clone();
+    let child = Subscription::empty();
+    child.add_teardown(move || child_tx.send("child").unwrap());
+    parent.add(child.clone());
+
+    parent.add_teardown(move || tx.send("teardown").unwrap());
+
+    parent.unsubscribe();
+    assert!(child.is_closed());
+    assert_eq!(rx.try_iter().collect::<Vec<&str>>(), vec!["unsubscriber", "child", "teardown"]);
+
+    // a closed subscription doesn't execute its teardowns again
+    parent.unsubscribe();
+    assert!(rx.try_iter().next().is_none());
+}
+
+#[test]
+fn observable_remove_child() {
+    let mut parent = Subscription::empty();
+    let child = Subscription::empty();
+
+    parent.add(child.clone());
+    parent.remove(&child);
+    parent.unsubscribe();
+
+    assert!(!child.is_closed());
+}
+
+#[test]
+fn observable_add_to_closed() {
+    let mut parent = Subscription::empty();
+    parent.unsubscribe();
+
+    let child = Subscription::empty();
+    parent.add(child.clone());
+    assert!(child.is_closed());
+
+    let (tx, rx) = channel();
+    parent.add_teardown(move || tx.send(()).unwrap());
+    assert_eq!(rx.try_iter().count(), 1);
+}
+
+#[test]
+fn observable_clone() {
+    let subscription = Subscription::empty();
+    let mut other = subscription.clone();
+
+    other.unsubscribe();
+    assert!(subscription.is_closed());
+}
+
+#[test]
+fn guard_drop() {
+    let subscription = Subscription::empty();
+
+    {
+        let _guard = subscription.clone().into_guard();
+        assert!(!subscription.is_closed());
+    }
+
+    assert!(subscription.is_closed());
+}
+
+#[test]
+fn guard_interval() {
+    let (tx, rx) = channel();
+
+    {
+        let _guard = interval(Duration::from_millis(1), NewThreadScheduler::new())
+            .subscribe_next(move |value| { let _ = tx.send(*value); })
+            .into_guard();
+
+        assert_eq!(rx.recv().unwrap(), 0);
+    }
+
+    // the sender is dropped with the interval task, once the interval is stopped
+    while rx.recv_timeout(Duration::from_secs(1)).is_ok() {}
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn dispose_bag() {
+    let subject = Subject::new();
+    let subscription = Subscription::empty();
+
+    {
+        let mut bag = DisposeBag::new();
+        bag.add(subscription.clone());
+        bag.add(subject.subscribe_next(|_: &i32| {}));
+        bag.add(subject.subscribe_next(|_: &i32| {}).into_guard());
+
+        assert_eq!(bag.len(), 3);
+        assert_eq!(subject.observer_count(), 2);
+    }
+
+    assert!(subscription.is_closed());
+    assert_eq!(subject.observer_count(), 0);
+}
+
+#[test]
+fn dispose_bag_unsubscribe() {
+    let mut bag = DisposeBag::new();
+    let subscription = Subscription::empty();
+    bag.add(subscription.clone());
+
+    bag.unsubscribe();
+    assert!(subscription.is_closed());
+    assert!(bag.is_empty());
+}
+
+fn subject_observer() -> Arc<SubjectObserver<i32>> {
     let observer: Observer<i32> = Observer::new(
 		|value| println!("{}", value),
 		|e| println!("{}", e),
 		|| println!("complete"),
 	);
-    let mut observers = Vec::new();
-    observers.push(Some(observer));
+    Arc::new(SubjectObserver::new(Subscriber::new(observer)))
+}
 
-    let observers_ref = &RefCell::new(observers);
-    let subscription = SubjectSubscription::new(observers_ref);
+#[test]
+fn subject_new() {
+    let mut observers = SubjectObservers::new();
+    let key = observers.insert(subject_observer());
 
-    assert_eq!(subscription.closed, false);
+    let observers_ref = &Arc::new(Mutex::new(observers));
+    let subscription = SubjectSubscription::new(observers_ref, key);
+
+    assert!(!subscription.closed);
 }
 
 #[test]
 fn subject_unsubscribe() {
-    let observer: Observer<i32> = Observer::new(
-		|value| println!("{}", value),
-		|e| println!("{}", e),
-		|| println!("complete"),
-	);
-    let mut observers = Vec::new();
-    observers.push(Some(observer));
+    let mut observers = SubjectObservers::new();
+    let observer = subject_observer();
+    let key = observers.insert(observer.clone());
 
-    let observers_ref = &RefCell::new(observers);
-    let mut subscription = SubjectSubscription::new(observers_ref);
+    let observers_ref = &Arc::new(Mutex::new(observers));
+    let mut subscription = SubjectSubscription::new(observers_ref, key);
 
     subscription.unsubscribe();
 
-    assert_eq!(subscription.closed, true);
+    assert!(subscription.closed);
+    assert!(observer.is_closed());
+    assert!(observers_ref.lock().unwrap().is_empty());
 }
 
 #[test]
 fn subject_multiple_unsubscribe() {
-    let observer_a: Observer<i32> = Observer::new(
-		|value| println!("{}", value),
-		|e| println!("{}", e),
-		|| println!("complete"),
-	);
-    let mut observers = Vec::new();
-    observers.push(Some(observer_a));
+    let mut observers = SubjectObservers::new();
+    let key = observers.insert(subject_observer());
 
-    let observers_ref = RefCell::new(observers);
-    let mut first = SubjectSubscription::new(&observers_ref);
+    let observers_ref = Arc::new(Mutex::new(observers));
+    let mut first = SubjectSubscription::new(&observers_ref, key);
 
-    let observer_b: Observer<i32> = Observer::new(
-		|value| println!("{}", value),
-		|e| println!("{}", e),
-		|| println!("complete"),
-	);
-    observers_ref.borrow_mut().push(Some(observer_b));
-    let mut second = SubjectSubscription::new(&observers_ref);
+    let key = observers_ref.lock().unwrap().insert(subject_observer());
+    let mut second = SubjectSubscription::new(&observers_ref, key);
 
     first.unsubscribe();
-    assert_eq!(first.closed, true);
+    assert!(first.closed);
+    assert_eq!(observers_ref.lock().unwrap().len(), 1);
 
     second.unsubscribe();
-    assert_eq!(second.closed, true);
-}
\ No newline at end of file
+    assert!(second.closed);
+    assert!(observers_ref.lock().unwrap().is_empty());
+}
+
+#[test]
+fn subject_stale_unsubscribe() {
+    let observers_ref = Arc::new(Mutex::new(SubjectObservers::new()));
+
+    let key = observers_ref.lock().unwrap().insert(subject_observer());
+    let mut stale = SubjectSubscription::new(&observers_ref, key);
+
+    // the subject drops its observers, e.g. once it completes, and a new observer subscribes
+    observers_ref.lock().unwrap().clear();
+    let observer = subject_observer();
+    observers_ref.lock().unwrap().insert(observer.clone());
+
+    stale.unsubscribe();
+    assert!(!observer.is_closed());
+    assert_eq!(observers_ref.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn subject_reuse_slot() {
+    let mut observers = SubjectObservers::new();
+    let (first, second, third) = (subject_observer(), subject_observer(), subject_observer());
+
+    observers.insert(first.clone());
+    let key = observers.insert(second.clone());
+    observers.remove(key);
+
+    // the slot of the removed observer is reused, but the observers keep their subscription order
+    let key = observers.insert(third.clone());
+    observers.insert(second.clone());
+    assert_eq!(observers.len(), 3);
+    assert!(observers.iter().zip(&[first, third, second]).all(|(observer, expected)| Arc::ptr_eq(observer, expected)));
+
+    // a removed key doesn't match the observer of its reused slot
+    observers.remove(key);
+    assert!(observers.remove(key).is_none());
+    assert_eq!(observers.len(), 2);
+}
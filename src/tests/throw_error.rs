@@ -0,0 +1,27 @@
+use std::sync::mpsc::channel;
+
+use crate::error::RxError;
+use crate::operators::throw_error;
+use crate::tests::utils::is_completed;
+
+#[test]
+fn create() {
+    let obs = throw_error::<i32, _>(RxError::EmptySequence);
+
+    let (tx, rx) = channel();
+    let subscription = obs.subscribe_error(move |e| tx.send(e.to_string()).unwrap());
+
+    assert_eq!(rx.try_iter().collect::<Vec<String>>(), vec!["sequence contains no elements"]);
+    assert!(subscription.is_closed());
+    assert!(!is_completed(&obs));
+}
+
+#[test]
+fn custom_error() {
+    let obs = throw_error::<i32, _>(404u16);
+
+    let (tx, rx) = channel();
+    obs.subscribe_error(move |e| tx.send(*e).unwrap());
+
+    assert_eq!(rx.try_recv(), Ok(404));
+}
@@ -0,0 +1,75 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::operators::{interval_with_error, of, timer, timer_with_error, timer_with_period};
+use crate::scheduler::TestScheduler;
+use crate::subscription::Unsubscribable;
+use crate::testing::MarbleTester;
+
+#[test]
+fn timer_once() {
+    let tester = MarbleTester::new();
+    let obs = timer(Duration::from_millis(3), tester.scheduler());
+
+    tester.expect_observable(obs).to_be("---(a|)", &[('a', 0)]);
+    tester.flush();
+}
+
+#[test]
+fn timer_period() {
+    let scheduler = TestScheduler::new();
+    let obs = timer_with_period(Duration::from_millis(5), Duration::from_millis(10), scheduler.clone());
+
+    let emitted = Arc::new(Mutex::new(Vec::new()));
+    let (next_emitted, next_scheduler) = (emitted.clone(), scheduler.clone());
+    let mut subscription = obs.subscribe_next(move |value| {
+        next_emitted.lock().unwrap().push((*value, next_scheduler.elapsed().as_millis()));
+    });
+
+    scheduler.advance_by(Duration::from_millis(30));
+    assert_eq!(*emitted.lock().unwrap(), vec![(0, 5), (1, 15), (2, 25)]);
+
+    subscription.unsubscribe();
+    scheduler.advance_by(Duration::from_millis(100));
+    assert_eq!(emitted.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn timer_unsubscribe() {
+    let scheduler = TestScheduler::new();
+    let obs = timer(Duration::from_millis(10), scheduler.clone());
+
+    let emitted = Arc::new(Mutex::new(Vec::new()));
+    let next_emitted = emitted.clone();
+    let mut subscription = obs.subscribe_next(move |value| next_emitted.lock().unwrap().push(*value));
+
+    subscription.unsubscribe();
+    scheduler.advance_by(Duration::from_millis(20));
+    assert!(emitted.lock().unwrap().is_empty());
+}
+
+#[test]
+fn timer_custom_error() {
+    let tester = MarbleTester::new();
+    let scheduler = tester.scheduler();
+    let obs = of(&[1, 2])
+        .map_err(|e| e.to_string())
+        .delay_when(move |value| timer_with_error(Duration::from_millis(*value as u64), scheduler.clone()));
+
+    tester.expect_observable(obs).to_be("-a(b|)", &[('a', 1), ('b', 2)]);
+    tester.flush();
+}
+
+#[test]
+fn interval_custom_error() {
+    let scheduler = TestScheduler::new();
+    let obs = interval_with_error::<_, String>(Duration::from_millis(10), scheduler.clone());
+
+    let emitted = Arc::new(Mutex::new(Vec::new()));
+    let next_emitted = emitted.clone();
+    let mut subscription = obs.subscribe_next(move |value| next_emitted.lock().unwrap().push(*value));
+
+    scheduler.advance_by(Duration::from_millis(30));
+    subscription.unsubscribe();
+    assert_eq!(*emitted.lock().unwrap(), vec![0, 1, 2]);
+}
\ No newline at end of file
@@ -1,9 +1,11 @@
 use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
 
+use crate::error::RxError;
 use crate::observable::Observable;
-use crate::observer::Observer;
+use crate::observer::ObserverLike;
 
-pub fn values_sent<T>(observable: &Observable<T, Observer<T>>, expected: &[T]) -> bool
+pub fn values_sent<T>(observable: &Observable<T>, expected: &[T]) -> bool
     where T: 'static + Clone + PartialEq + Sync + Send {
     let (tx, rx) = channel();
 
@@ -28,9 +30,32 @@ pub fn values_sent<T>(observable: &Observable<T, Observer<T>>, expected: &[T]) -
     true
 }
 
-pub fn is_completed<T>(observable: &Observable<T, Observer<T>>) -> bool {
+pub fn is_completed<'a, T: 'a>(observable: &Observable<'a, T>) -> bool {
     let (tx, rx) = channel();
     observable.subscribe_complete(move || tx.send(true).unwrap());
 
     rx.try_recv().is_ok()
+}
+
+/// `TakeObserver` records the values it receives, and is closed once it received `count` values.
+pub struct TakeObserver<T> {
+    pub count: usize,
+    pub received: Arc<Mutex<Vec<T>>>,
+}
+
+impl<T: Clone> ObserverLike for TakeObserver<T> {
+    type Value = T;
+    type Error = RxError;
+
+    fn next(&self, value: &Self::Value) {
+        self.received.lock().unwrap().push(value.clone());
+    }
+
+    fn error(&self, _e: &Self::Error) {}
+
+    fn complete(&mut self) {}
+
+    fn is_closed(&self) -> bool {
+        self.received.lock().unwrap().len() >= self.count
+    }
 }
\ No newline at end of file